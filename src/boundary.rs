@@ -0,0 +1,144 @@
+use crate::triangulate::Triangulation;
+use std::collections::HashMap;
+
+type Segment = [[f64; 2]; 2];
+
+/// The rings of a triangulated polygon, bucketed on a grid so that the nearest one to a point
+/// is found without measuring every segment.
+pub(crate) struct Boundary {
+    segments: Vec<Segment>,
+    origin: [f64; 2],
+    cell_size: f64,
+    columns: usize,
+    rows: usize,
+    /// Indices into `segments` of the segments crossing each cell, row by row.
+    cells: Vec<Vec<usize>>,
+}
+
+impl Boundary {
+    /// The edges that only one triangle of `triangulation` has.
+    pub(crate) fn new<Scalar: geo_types::CoordFloat>(
+        triangulation: &Triangulation<Scalar>,
+    ) -> Self {
+        let mut edges = HashMap::<_, usize>::new();
+        for triangle in triangulation.triangle_indices.chunks_exact(3) {
+            for corner in 0..3 {
+                let (a, b) = (triangle[corner], triangle[(corner + 1) % 3]);
+                *edges.entry((a.min(b), a.max(b))).or_default() += 1;
+            }
+        }
+        let point = |index: usize| {
+            [0, 1].map(|axis| {
+                triangulation.vertices[index * 2 + axis]
+                    .to_f64()
+                    .unwrap_or(f64::NAN)
+            })
+        };
+        let segments = edges
+            .into_iter()
+            .filter(|(_, count)| *count == 1)
+            .map(|((a, b), _)| [point(a), point(b)])
+            .filter(|segment| segment.as_flattened().iter().all(|c| c.is_finite()))
+            .collect();
+        Self::from_segments(segments)
+    }
+
+    /// Sizes the grid to have about as many cells as there are segments.
+    fn from_segments(segments: Vec<Segment>) -> Self {
+        let (mut min, mut max) = ([f64::INFINITY; 2], [f64::NEG_INFINITY; 2]);
+        for point in segments.iter().flatten() {
+            for axis in 0..2 {
+                min[axis] = min[axis].min(point[axis]);
+                max[axis] = max[axis].max(point[axis]);
+            }
+        }
+        let (width, height) = ((max[0] - min[0]).max(0.), (max[1] - min[1]).max(0.));
+        let num_segments = segments.len().max(1) as f64;
+        let cell_size = match (width * height / num_segments).sqrt() {
+            size if size > 0. => size,
+            _ if width.max(height) > 0. => width.max(height) / num_segments,
+            _ => 1.,
+        };
+        let columns = (width / cell_size).floor() as usize + 1;
+        let rows = (height / cell_size).floor() as usize + 1;
+
+        let mut boundary = Boundary {
+            segments: Vec::new(),
+            origin: if segments.is_empty() { [0.; 2] } else { min },
+            cell_size,
+            columns,
+            rows,
+            cells: vec![Vec::new(); columns * rows],
+        };
+        for (index, segment) in segments.iter().enumerate() {
+            boundary.insert(index, segment);
+        }
+        boundary.segments = segments;
+        boundary
+    }
+
+    /// Adds `index` to every cell `segment` crosses, a row at a time.
+    fn insert(&mut self, index: usize, &[[ax, ay], [bx, by]]: &Segment) {
+        let [first_row, last_row] = [ay.min(by), ay.max(by)].map(|y| self.row(y));
+        for row in first_row..=last_row {
+            // The part of the segment within the row.
+            let bottom = self.origin[1] + row as f64 * self.cell_size;
+            let x_at = |y: f64| match by - ay {
+                dy if dy != 0. => ax + (bx - ax) * ((y - ay) / dy).clamp(0., 1.),
+                _ => ax,
+            };
+            let (from, to) = match ay == by {
+                true => (ax, bx),
+                false => (x_at(bottom), x_at(bottom + self.cell_size)),
+            };
+            for column in self.column(from.min(to))..=self.column(from.max(to)) {
+                self.cells[row * self.columns + column].push(index);
+            }
+        }
+    }
+
+    fn column(&self, x: f64) -> usize {
+        (((x - self.origin[0]) / self.cell_size).floor().max(0.) as usize).min(self.columns - 1)
+    }
+
+    fn row(&self, y: f64) -> usize {
+        (((y - self.origin[1]) / self.cell_size).floor().max(0.) as usize).min(self.rows - 1)
+    }
+
+    /// Distance from `point` to the nearest segment, searching rings of cells outward from
+    /// `point`'s until no unsearched cell can hold a nearer one.
+    pub(crate) fn distance(&self, point: [f64; 2]) -> f64 {
+        let (column, row) = (self.column(point[0]), self.row(point[1]));
+        let mut nearest = f64::INFINITY;
+        for ring in 0..=self.columns.max(self.rows) {
+            // Cells beyond this ring are at least `ring` cells away.
+            if nearest <= ring.saturating_sub(1) as f64 * self.cell_size {
+                break;
+            }
+            let rows = row.saturating_sub(ring)..=(row + ring).min(self.rows - 1);
+            for cell_row in rows {
+                let on_edge = cell_row + ring == row || cell_row == row + ring;
+                let columns = column.saturating_sub(ring)..=(column + ring).min(self.columns - 1);
+                for cell_column in columns {
+                    if !on_edge && cell_column + ring != column && cell_column != column + ring {
+                        continue;
+                    }
+                    for index in &self.cells[cell_row * self.columns + cell_column] {
+                        nearest = nearest.min(segment_distance(&self.segments[*index], point));
+                    }
+                }
+            }
+        }
+        nearest
+    }
+}
+
+fn segment_distance(&[[ax, ay], [bx, by]]: &Segment, [x, y]: [f64; 2]) -> f64 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let length_squared = dx * dx + dy * dy;
+    let t = match length_squared > 0. {
+        true => (((x - ax) * dx + (y - ay) * dy) / length_squared).clamp(0., 1.),
+        false => 0.,
+    };
+    (x - ax - t * dx).hypot(y - ay - t * dy)
+}
@@ -0,0 +1,37 @@
+#import bevy_pbr::mesh_functions::{get_world_from_local, mesh_position_local_to_clip}
+
+struct BoundaryGradient {
+    edge_color: vec4<f32>,
+    inner_color: vec4<f32>,
+    width: f32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> gradient: BoundaryGradient;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) boundary_distance: f32,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) boundary_distance: f32,
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = mesh_position_local_to_clip(
+        get_world_from_local(vertex.instance_index),
+        vec4<f32>(vertex.position, 1.0),
+    );
+    out.boundary_distance = vertex.boundary_distance;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let t = smoothstep(0.0, max(gradient.width, 1e-6), in.boundary_distance);
+    return mix(gradient.edge_color, gradient.inner_color, t);
+}
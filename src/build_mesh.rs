@@ -1,8 +1,20 @@
-use geo_traits::{CoordTrait, GeometryType};
-use std::iter;
+use geo_traits::{
+    GeometryCollectionTrait, GeometryTrait, GeometryType, LineStringTrait, MultiLineStringTrait,
+    MultiPointTrait, MultiPolygonTrait, PolygonTrait,
+};
 
 pub trait BuildMesh {
     fn build(self) -> Result<crate::GeometryMesh, crate::Error>;
+
+    /// Like `build`, but leaves the builder untouched, so it can keep accumulating or be
+    /// cleared and reused.
+    fn build_ref(&self) -> Result<crate::GeometryMesh, crate::Error>;
+
+    /// Like `build_ref`, but splits into several meshes where a single one would exceed
+    /// `MeshOptions::index_width`, instead of failing with `Error::IndexOverflow`.
+    fn build_meshes(&self) -> Result<Vec<crate::GeometryMesh>, crate::Error> {
+        Ok(vec![self.build_ref()?])
+    }
 }
 
 pub struct BuildBevyMeshesContext<Scalar: geo_types::CoordFloat> {
@@ -21,13 +33,204 @@ impl<Scalar: geo_types::CoordFloat> Default for BuildBevyMeshesContext<Scalar> {
     }
 }
 
+impl<Scalar: geo_types::CoordFloat> BuildBevyMeshesContext<Scalar> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Passes `options` down to every builder.
+    pub fn with_options(mut self, options: crate::options::MeshOptions) -> Self {
+        self.point_mesh_builder = self.point_mesh_builder.with_options(options.clone());
+        self.line_string_mesh_builder = self.line_string_mesh_builder.with_options(options.clone());
+        self.polygon_mesh_builder = self.polygon_mesh_builder.with_options(options);
+        self
+    }
+
+    /// Adds `geometry` to the builder for its kind. Collections are split across builders.
+    ///
+    /// Each call adds a feature, as does each member of a geometry collection passed in, and
+    /// points are tagged with the index of theirs in `SpritePosition::feature`.
+    pub fn add_geometry(
+        &mut self,
+        geometry: &impl geo_traits::GeometryTrait<T = Scalar>,
+    ) -> Result<(), crate::Error> {
+        let GeometryType::GeometryCollection(collection) = geometry.as_type() else {
+            self.point_mesh_builder.start_feature();
+            return populate_geometry_mesh_builders(geometry, self);
+        };
+        for member in collection.geometries() {
+            self.point_mesh_builder.start_feature();
+            populate_geometry_mesh_builders(&member, self)?;
+        }
+        Ok(())
+    }
+
+    /// Adds `geometry` as a feature styled by `style` resolved against its `properties`.
+    pub fn add_feature(
+        &mut self,
+        geometry: &impl geo_traits::GeometryTrait<T = Scalar>,
+        properties: &dyn crate::style::Properties,
+        style: &crate::style::Style,
+    ) -> Result<(), crate::Error> {
+        self.set_style(&style.resolve(properties));
+        self.add_geometry(geometry)
+    }
+
+    /// Styles geometries added from now on in every builder.
+    pub fn set_style(&mut self, style: &crate::style::FeatureStyle) {
+        self.point_mesh_builder.set_style(style);
+        self.line_string_mesh_builder.set_style(style);
+        self.polygon_mesh_builder.set_style(style);
+    }
+
+    /// Reserves room in each builder for what `add_geometry` would add for `geometry`.
+    pub fn reserve_for(&mut self, geometry: &impl GeometryTrait) {
+        let counts = Counts::of(geometry);
+        self.point_mesh_builder.reserve(counts.points);
+        self.line_string_mesh_builder.reserve_counts(&counts);
+        self.polygon_mesh_builder.reserve_counts(&counts);
+    }
+
+    /// Removes all geometries, keeping the allocated buffers for reuse.
+    pub fn clear(&mut self) {
+        self.point_mesh_builder.clear();
+        self.line_string_mesh_builder.clear();
+        self.polygon_mesh_builder.clear();
+    }
+
+    /// Number of points, line string vertices and fill vertices added so far.
+    pub fn len(&self) -> usize {
+        self.point_mesh_builder.len()
+            + self.line_string_mesh_builder.len()
+            + self.polygon_mesh_builder.len()
+    }
+
+    /// Whether no builder has anything to build, counting polygon outlines built without a fill.
+    pub fn is_empty(&self) -> bool {
+        self.point_mesh_builder.is_empty()
+            && self.line_string_mesh_builder.is_empty()
+            && self.polygon_mesh_builder.is_empty()
+    }
+}
+
+/// Builds the first non-empty of the point, line string and polygon builders.
+impl<Scalar: geo_types::CoordFloat> BuildMesh for BuildBevyMeshesContext<Scalar> {
+    fn build(self) -> Result<crate::GeometryMesh, crate::Error> {
+        [
+            self.point_mesh_builder.build(),
+            self.line_string_mesh_builder.build(),
+            self.polygon_mesh_builder.build(),
+        ]
+        .into_iter()
+        .find(|prepared_mesh| prepared_mesh.is_ok())
+        .unwrap_or(Err(crate::Error::CouldNotBuildMesh))
+    }
+
+    fn build_ref(&self) -> Result<crate::GeometryMesh, crate::Error> {
+        [
+            self.point_mesh_builder.build_ref(),
+            self.line_string_mesh_builder.build_ref(),
+            self.polygon_mesh_builder.build_ref(),
+        ]
+        .into_iter()
+        .find(|prepared_mesh| prepared_mesh.is_ok())
+        .unwrap_or(Err(crate::Error::CouldNotBuildMesh))
+    }
+
+    /// Builds the meshes of every non-empty builder.
+    fn build_meshes(&self) -> Result<Vec<crate::GeometryMesh>, crate::Error> {
+        if self.is_empty() {
+            return Err(crate::Error::CouldNotBuildMesh);
+        }
+        let mut meshes = Vec::new();
+        if !self.point_mesh_builder.is_empty() {
+            meshes.extend(self.point_mesh_builder.build_meshes()?);
+        }
+        if !self.line_string_mesh_builder.is_empty() {
+            meshes.extend(BuildMesh::build_meshes(&self.line_string_mesh_builder)?);
+        }
+        if !self.polygon_mesh_builder.is_empty() {
+            meshes.extend(BuildMesh::build_meshes(&self.polygon_mesh_builder)?);
+        }
+        Ok(meshes)
+    }
+}
+
+/// How much of a geometry goes to each builder.
+#[derive(Default)]
+pub(crate) struct Counts {
+    pub(crate) points: usize,
+    pub(crate) line_coords: usize,
+    pub(crate) line_strings: usize,
+    pub(crate) polygons: usize,
+    pub(crate) exterior_coords: usize,
+    pub(crate) interiors: usize,
+    pub(crate) interior_coords: usize,
+}
+
+impl Counts {
+    pub(crate) fn of(geometry: &impl GeometryTrait) -> Self {
+        let mut counts = Counts::default();
+        counts.add_geometry(geometry);
+        counts
+    }
+
+    fn add_geometry(&mut self, geometry: &impl GeometryTrait) {
+        match geometry.as_type() {
+            GeometryType::Point(_) => self.points += 1,
+            GeometryType::LineString(g) => self.add_line_string(g.num_coords()),
+            GeometryType::Polygon(g) => self.add_polygon(g),
+            GeometryType::MultiPoint(g) => self.points += g.num_points(),
+            GeometryType::MultiLineString(g) => {
+                for line_string in g.line_strings() {
+                    self.add_line_string(line_string.num_coords());
+                }
+            }
+            GeometryType::MultiPolygon(g) => {
+                for polygon in g.polygons() {
+                    self.add_polygon(&polygon);
+                }
+            }
+            GeometryType::GeometryCollection(g) => {
+                for geometry in g.geometries() {
+                    self.add_geometry(&geometry);
+                }
+            }
+            GeometryType::Triangle(_) => self.add_exterior(3),
+            GeometryType::Rect(_) => self.add_exterior(4),
+            GeometryType::Line(_) => self.add_line_string(2),
+        }
+    }
+
+    fn add_line_string(&mut self, num_coords: usize) {
+        self.line_strings += 1;
+        self.line_coords += num_coords;
+    }
+
+    fn add_polygon(&mut self, polygon: &impl PolygonTrait) {
+        self.add_exterior(
+            polygon
+                .exterior()
+                .map_or(0, |exterior| exterior.num_coords()),
+        );
+        self.interiors += polygon.num_interiors();
+        self.interior_coords += polygon
+            .interiors()
+            .map(|interior| interior.num_coords())
+            .sum::<usize>();
+    }
+
+    fn add_exterior(&mut self, num_coords: usize) {
+        self.polygons += 1;
+        self.exterior_coords += num_coords;
+    }
+}
+
 fn populate_point_mesh_builders<Scalar: geo_types::CoordFloat>(
     point: &impl geo_traits::PointTrait<T = Scalar>,
     ctx: &mut BuildBevyMeshesContext<Scalar>,
 ) -> Result<(), crate::Error> {
-    if let Some(coord) = point.coord() {
-        ctx.point_mesh_builder.add_coord(coord);
-    }
+    ctx.point_mesh_builder.add_point(point);
     Ok(())
 }
 
@@ -35,8 +238,7 @@ fn populate_line_string_mesh_builders<Scalar: geo_types::CoordFloat>(
     line_string: &impl geo_traits::LineStringTrait<T = Scalar>,
     ctx: &mut BuildBevyMeshesContext<Scalar>,
 ) -> Result<(), crate::Error> {
-    ctx.line_string_mesh_builder
-        .add_coords(line_string.coords())
+    ctx.line_string_mesh_builder.add_line_string(line_string)
 }
 
 fn populate_polygon_mesh_builders<Scalar: geo_types::CoordFloat>(
@@ -50,9 +252,7 @@ fn populate_multi_point_mesh_builders<Scalar: geo_types::CoordFloat>(
     multi_point: &impl geo_traits::MultiPointTrait<T = Scalar>,
     ctx: &mut BuildBevyMeshesContext<Scalar>,
 ) -> Result<(), crate::Error> {
-    for point in multi_point.points() {
-        populate_point_mesh_builders(&point, ctx)?;
-    }
+    ctx.point_mesh_builder.add_multi_point(multi_point);
     Ok(())
 }
 
@@ -90,54 +290,24 @@ fn populate_triangle_mesh_builders<Scalar: geo_types::CoordFloat>(
     triangle: &impl geo_traits::TriangleTrait<T = Scalar>,
     ctx: &mut BuildBevyMeshesContext<Scalar>,
 ) -> Result<(), crate::Error> {
-    // TODO: build with earcutr directly
-    let polygon = geo_types::Polygon::new(
-        geo_types::LineString::new(
-            triangle
-                .coords()
-                .into_iter()
-                .map(|c| geo_types::Coord { x: c.x(), y: c.y() })
-                .collect(),
-        ),
-        vec![],
-    );
-    ctx.polygon_mesh_builder.add_polygon(&polygon)
+    ctx.polygon_mesh_builder.add_triangle(triangle)
 }
 
 fn populate_rect_mesh_builders<Scalar: geo_types::CoordFloat>(
     rect: &impl geo_traits::RectTrait<T = Scalar>,
     ctx: &mut BuildBevyMeshesContext<Scalar>,
 ) -> Result<(), crate::Error> {
-    ctx.polygon_mesh_builder
-        .add_polygon_from_exterior_coords(rect_coords_iter(rect))
-}
-
-fn rect_coords_iter<
-    'a,
-    Scalar: geo_types::CoordFloat + 'a,
-    PointType: geo_traits::CoordTrait<T = Scalar> + 'a,
->(
-    rect: &'a impl geo_traits::RectTrait<T = Scalar, CoordType<'a> = PointType>,
-) -> impl Iterator<Item = (Scalar, Scalar)> + Clone {
-    let (min, max) = (rect.min(), rect.max());
-    [
-        (min.x(), min.y()),
-        (min.x(), max.y()),
-        (max.x(), max.y()),
-        (max.x(), min.y()),
-    ]
-    .into_iter()
+    ctx.polygon_mesh_builder.add_rect(rect)
 }
 
 fn populate_line_mesh_builders<Scalar: geo_types::CoordFloat>(
     line: &impl geo_traits::LineTrait<T = Scalar>,
     ctx: &mut BuildBevyMeshesContext<Scalar>,
 ) -> Result<(), crate::Error> {
-    let iter = iter::once(line.start()).chain(iter::once(line.end()));
-    ctx.line_string_mesh_builder.add_coords(iter)
+    ctx.line_string_mesh_builder.add_line(line)
 }
 
-pub fn populate_geometry_mesh_builders<
+fn populate_geometry_mesh_builders<
     Scalar: geo_types::CoordFloat,
     G: geo_traits::GeometryTrait<T = Scalar>,
 >(
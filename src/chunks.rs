@@ -0,0 +1,47 @@
+use std::ops::Range;
+
+/// Where a builder's buffers are split into separate meshes, so that no mesh has more vertices
+/// than its index width can address. Indices are relative to the start of their mesh.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Chunks {
+    /// Vertex and index offsets of every mesh after the first.
+    starts: Vec<(usize, usize)>,
+}
+
+impl Chunks {
+    pub(crate) fn vertex_start(&self) -> usize {
+        self.starts.last().map_or(0, |(vertex, _)| *vertex)
+    }
+
+    pub(crate) fn index_start(&self) -> usize {
+        self.starts.last().map_or(0, |(_, index)| *index)
+    }
+
+    /// Starts a new mesh at the end of the buffers.
+    pub(crate) fn start(&mut self, num_vertices: usize, num_indices: usize) {
+        self.starts.push((num_vertices, num_indices));
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.starts.clear();
+    }
+
+    /// Number of meshes the buffers are split into.
+    pub(crate) fn len(&self) -> usize {
+        self.starts.len() + 1
+    }
+
+    /// Vertex and index ranges of each mesh.
+    pub(crate) fn ranges(
+        &self,
+        num_vertices: usize,
+        num_indices: usize,
+    ) -> impl Iterator<Item = (Range<usize>, Range<usize>)> + '_ {
+        let starts = || [(0, 0)].into_iter().chain(self.starts.iter().copied());
+        starts()
+            .zip(starts().skip(1).chain([(num_vertices, num_indices)]))
+            .map(|((vertex_start, index_start), (vertex_end, index_end))| {
+                (vertex_start..vertex_end, index_start..index_end)
+            })
+    }
+}
@@ -0,0 +1,110 @@
+/// Units of a `DashPattern`'s lengths.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DashUnits {
+    /// Geo units: line strings and rings are split into dashes when meshing.
+    #[default]
+    Geo,
+    /// Pixels: lines are left whole and get `ATTRIBUTE_LINE_DISTANCE`, for a material such as
+    /// `DashedLineMaterial` to dash them at the current zoom.
+    Screen,
+}
+
+/// Alternating on and off lengths, starting with on, repeated along every line string and ring.
+/// An odd number of lengths is repeated twice per period, as in SVG's `stroke-dasharray`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DashPattern {
+    pub lengths: Vec<f64>,
+    /// Distance into the pattern at which lines start.
+    pub offset: f64,
+    pub units: DashUnits,
+}
+
+impl DashPattern {
+    pub fn new(lengths: impl Into<Vec<f64>>, units: DashUnits) -> Self {
+        DashPattern {
+            lengths: lengths.into(),
+            offset: 0.,
+            units,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Whether the pattern can be applied: lengths must be finite and non-negative, with some
+    /// of them positive. Meshing fails with `Error::InvalidDashPattern` otherwise.
+    pub fn is_valid(&self) -> bool {
+        self.lengths
+            .iter()
+            .all(|length| length.is_finite() && *length >= 0.)
+            && self.lengths.iter().any(|length| *length > 0.)
+            && self.offset.is_finite()
+    }
+}
+
+/// Walks a `DashPattern` in geo units along a line string.
+#[derive(Clone, Debug)]
+pub(crate) struct Dasher {
+    /// One period of the pattern, with an even number of lengths.
+    lengths: Vec<f64>,
+    offset: f64,
+    index: usize,
+    /// Length left of `lengths[index]`.
+    remaining: f64,
+}
+
+impl Dasher {
+    pub(crate) fn new(pattern: &DashPattern) -> Option<Self> {
+        if pattern.units != DashUnits::Geo || !pattern.is_valid() {
+            return None;
+        }
+        let mut lengths = pattern.lengths.clone();
+        if lengths.len() % 2 == 1 {
+            lengths.extend_from_within(..);
+        }
+        let mut dasher = Dasher {
+            lengths,
+            offset: pattern.offset,
+            index: 0,
+            remaining: 0.,
+        };
+        dasher.restart();
+        Some(dasher)
+    }
+
+    /// Goes back to where the pattern starts, for a new line string.
+    pub(crate) fn restart(&mut self) {
+        let period = self.lengths.iter().sum::<f64>();
+        let mut offset = self.offset.rem_euclid(period);
+        for (index, length) in self.lengths.iter().enumerate() {
+            if offset < *length {
+                self.index = index;
+                self.remaining = length - offset;
+                return;
+            }
+            offset -= length;
+        }
+        // Only reachable through rounding.
+        self.index = 0;
+        self.remaining = self.lengths[0];
+    }
+
+    pub(crate) fn is_on(&self) -> bool {
+        self.index.is_multiple_of(2)
+    }
+
+    /// Moves up to `length` along the pattern. Returns how far the next switch between on and
+    /// off is, if it comes within `length`.
+    pub(crate) fn next_switch(&mut self, length: f64) -> Option<f64> {
+        if self.remaining >= length {
+            self.remaining -= length;
+            return None;
+        }
+        let step = self.remaining;
+        self.index = (self.index + 1) % self.lengths.len();
+        self.remaining = self.lengths[self.index];
+        Some(step)
+    }
+}
@@ -0,0 +1,52 @@
+#import bevy_pbr::mesh_functions::{get_world_from_local, mesh_position_local_to_clip}
+
+struct DashedLine {
+    color: vec4<f32>,
+    // On and off lengths in pixels, padded with zeros.
+    lengths: array<vec4<f32>, 2>,
+    period: f32,
+    offset: f32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> line: DashedLine;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) line_distance: f32,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) line_distance: f32,
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = mesh_position_local_to_clip(
+        get_world_from_local(vertex.instance_index),
+        vec4<f32>(vertex.position, 1.0),
+    );
+    out.line_distance = vertex.line_distance;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Geo units per pixel along the line.
+    let scale = length(vec2<f32>(dpdx(in.line_distance), dpdy(in.line_distance)));
+    let pixels = in.line_distance / max(scale, 1e-6) + line.offset;
+    var along = pixels - floor(pixels / line.period) * line.period;
+    for (var index = 0u; index < 8u; index++) {
+        let dash = line.lengths[index / 4u][index % 4u];
+        if along < dash {
+            if index % 2u == 1u {
+                discard;
+            }
+            break;
+        }
+        along -= dash;
+    }
+    return line.color;
+}
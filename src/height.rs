@@ -0,0 +1,259 @@
+use bevy::{image::Image, math::UVec3, render::render_resource::TextureFormat};
+
+/// Elevation data that meshes are draped onto.
+///
+/// Coordinates are those of the geometries being meshed.
+pub trait HeightSource: Send + Sync {
+    /// Elevation at `(x, y)`, or `None` outside the covered area.
+    fn height_at(&self, x: f64, y: f64) -> Option<f32>;
+
+    /// Distance between samples. Line segments are resampled at this spacing so they follow the
+    /// terrain instead of cutting through it between sparse vertices.
+    fn resolution(&self) -> Option<f64> {
+        None
+    }
+}
+
+/// A GeoTIFF-style affine transform from pixel `(column, row)` to geo coordinates, in GDAL
+/// order: `x = t[0] + column * t[1] + row * t[2]` and `y = t[3] + column * t[4] + row * t[5]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeoTransform(pub [f64; 6]);
+
+impl GeoTransform {
+    /// Transform of a north-up raster whose top-left pixel corner is at `top_left`.
+    pub fn north_up(top_left: [f64; 2], pixel_size: [f64; 2]) -> Self {
+        GeoTransform([
+            top_left[0],
+            pixel_size[0],
+            0.,
+            top_left[1],
+            0.,
+            -pixel_size[1],
+        ])
+    }
+
+    pub fn pixel_to_geo(&self, column: f64, row: f64) -> [f64; 2] {
+        let t = &self.0;
+        [
+            t[0] + column * t[1] + row * t[2],
+            t[3] + column * t[4] + row * t[5],
+        ]
+    }
+
+    /// Inverse of `pixel_to_geo`, or `None` if the transform is degenerate.
+    pub fn geo_to_pixel(&self, x: f64, y: f64) -> Option<[f64; 2]> {
+        let t = &self.0;
+        let determinant = t[1] * t[5] - t[2] * t[4];
+        if determinant == 0. {
+            return None;
+        }
+        let (dx, dy) = (x - t[0], y - t[3]);
+        Some([
+            (dx * t[5] - dy * t[2]) / determinant,
+            (dy * t[1] - dx * t[4]) / determinant,
+        ])
+    }
+
+    /// Length of a pixel's shorter side in geo units.
+    pub fn resolution(&self) -> f64 {
+        let t = &self.0;
+        t[1].hypot(t[4]).min(t[2].hypot(t[5]))
+    }
+}
+
+/// An in-memory elevation raster, with one row-major value per pixel.
+#[derive(Clone, Debug)]
+pub struct HeightmapGrid {
+    width: usize,
+    height: usize,
+    values: Vec<f32>,
+    transform: GeoTransform,
+}
+
+impl HeightmapGrid {
+    /// Fails with `Error::HeightmapSizeMismatch` unless there are `width * height` values.
+    pub fn new(
+        width: usize,
+        height: usize,
+        values: Vec<f32>,
+        transform: GeoTransform,
+    ) -> Result<Self, crate::Error> {
+        if width.checked_mul(height) != Some(values.len()) {
+            return Err(crate::Error::HeightmapSizeMismatch {
+                width,
+                height,
+                num_values: values.len(),
+            });
+        }
+        Ok(HeightmapGrid {
+            width,
+            height,
+            values,
+            transform,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    pub fn transform(&self) -> GeoTransform {
+        self.transform
+    }
+
+    /// `None` outside the grid.
+    pub fn value(&self, column: usize, row: usize) -> Option<f32> {
+        if column >= self.width || row >= self.height {
+            return None;
+        }
+        Some(self.values[row * self.width + column])
+    }
+}
+
+impl HeightSource for HeightmapGrid {
+    fn height_at(&self, x: f64, y: f64) -> Option<f32> {
+        sample(
+            self.width,
+            self.height,
+            &self.transform,
+            x,
+            y,
+            |column, row| self.value(column, row),
+        )
+    }
+
+    fn resolution(&self) -> Option<f64> {
+        Some(self.transform.resolution())
+    }
+}
+
+/// A Bevy `Image` used as a DEM: elevation is `offset + scale * red` for each pixel, with
+/// normalized formats read as `0.0..=1.0`.
+pub struct ImageHeightmap {
+    image: Image,
+    transform: GeoTransform,
+    scale: f32,
+    offset: f32,
+}
+
+impl ImageHeightmap {
+    pub fn new(
+        image: Image,
+        transform: GeoTransform,
+        scale: f32,
+        offset: f32,
+    ) -> Result<Self, crate::Error> {
+        let format = image.texture_descriptor.format;
+        if red_channel(format, &[0; 16]).is_none() || image.data.is_none() {
+            return Err(crate::Error::UnsupportedImageFormat(format));
+        }
+        Ok(ImageHeightmap {
+            image,
+            transform,
+            scale,
+            offset,
+        })
+    }
+
+    /// Copies the elevations into a `HeightmapGrid`, e.g. for `terrain_to_mesh`. Pixels that
+    /// cannot be read become NaN.
+    pub fn to_grid(&self) -> HeightmapGrid {
+        let (width, height) = (self.image.width() as usize, self.image.height() as usize);
+        let values = (0..height)
+            .flat_map(|row| (0..width).map(move |column| (column, row)))
+            .map(|(column, row)| self.value(column, row).unwrap_or(f32::NAN))
+            .collect();
+        HeightmapGrid {
+            width,
+            height,
+            values,
+            transform: self.transform,
+        }
+    }
+
+    fn value(&self, column: usize, row: usize) -> Option<f32> {
+        let bytes = self
+            .image
+            .pixel_bytes(UVec3::new(column as u32, row as u32, 0))?;
+        red_channel(self.image.texture_descriptor.format, bytes)
+            .map(|red| self.offset + self.scale * red)
+    }
+}
+
+impl HeightSource for ImageHeightmap {
+    fn height_at(&self, x: f64, y: f64) -> Option<f32> {
+        sample(
+            self.image.width() as usize,
+            self.image.height() as usize,
+            &self.transform,
+            x,
+            y,
+            |column, row| self.value(column, row),
+        )
+    }
+
+    fn resolution(&self) -> Option<f64> {
+        Some(self.transform.resolution())
+    }
+}
+
+fn red_channel(format: TextureFormat, bytes: &[u8]) -> Option<f32> {
+    match format {
+        TextureFormat::R8Unorm
+        | TextureFormat::Rg8Unorm
+        | TextureFormat::Rgba8Unorm
+        | TextureFormat::Rgba8UnormSrgb => Some(f32::from(bytes[0]) / f32::from(u8::MAX)),
+        TextureFormat::R16Unorm | TextureFormat::Rg16Unorm | TextureFormat::Rgba16Unorm => {
+            Some(f32::from(u16::from_le_bytes([bytes[0], bytes[1]])) / f32::from(u16::MAX))
+        }
+        TextureFormat::R32Float | TextureFormat::Rg32Float | TextureFormat::Rgba32Float => {
+            Some(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        }
+        _ => None,
+    }
+}
+
+/// Interpolates between pixel centres, splitting each cell along the diagonal from its top-left
+/// to its bottom-right sample. Terrain meshes use the same split, so draped geometry lies exactly
+/// on them.
+fn sample(
+    width: usize,
+    height: usize,
+    transform: &GeoTransform,
+    x: f64,
+    y: f64,
+    value: impl Fn(usize, usize) -> Option<f32>,
+) -> Option<f32> {
+    let [column, row] = transform.geo_to_pixel(x, y)?;
+    let (u, v) = (column - 0.5, row - 0.5);
+    let (max_u, max_v) = (width.checked_sub(1)? as f64, height.checked_sub(1)? as f64);
+    if !(0. ..=max_u).contains(&u) || !(0. ..=max_v).contains(&v) {
+        return None;
+    }
+
+    // Keep the last row and column inside a cell.
+    let (column, row) = (
+        (u.floor() as usize).min(width.saturating_sub(2)),
+        (v.floor() as usize).min(height.saturating_sub(2)),
+    );
+    let (fu, fv) = ((u - column as f64) as f32, (v - row as f64) as f32);
+    let (right, below) = ((column + 1).min(width - 1), (row + 1).min(height - 1));
+
+    let top_left = value(column, row)?;
+    let bottom_right = value(right, below)?;
+    Some(if fu >= fv {
+        let top_right = value(right, row)?;
+        top_left + fu * (top_right - top_left) + fv * (bottom_right - top_right)
+    } else {
+        let bottom_left = value(column, below)?;
+        top_left + fv * (bottom_left - top_left) + fu * (bottom_right - bottom_left)
+    })
+}
@@ -0,0 +1,37 @@
+#import bevy_pbr::mesh_view_bindings::view
+
+struct Vertex {
+    @location(0) position: vec3<f32>,
+
+    @location(8) i_position: vec3<f32>,
+    @location(9) i_size: f32,
+    @location(10) i_color: vec4<f32>,
+    @location(11) i_rotation: f32,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let c = cos(vertex.i_rotation);
+    let s = sin(vertex.i_rotation);
+    let marker = vertex.position * vertex.i_size;
+    let rotated = vec3<f32>(
+        c * marker.x - s * marker.y,
+        s * marker.x + c * marker.y,
+        marker.z,
+    );
+
+    var out: VertexOutput;
+    out.clip_position = view.clip_from_world * vec4<f32>(rotated + vertex.i_position, 1.0);
+    out.color = vertex.i_color;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
@@ -0,0 +1,316 @@
+use crate::point::PointInstance;
+use bevy::asset::embedded_asset;
+use bevy::camera::visibility::NoFrustumCulling;
+use bevy::core_pipeline::core_3d::Transparent3d;
+use bevy::ecs::query::QueryItem;
+use bevy::ecs::system::lifetimeless::{Read, SRes};
+use bevy::ecs::system::SystemParamItem;
+use bevy::mesh::{MeshVertexBufferLayoutRef, VertexBufferLayout};
+use bevy::pbr::{
+    MeshPipeline, MeshPipelineKey, RenderMeshInstances, SetMeshBindGroup, SetMeshViewBindGroup,
+    SetMeshViewBindingArrayBindGroup,
+};
+use bevy::prelude::{
+    App, AssetServer, Changed, Commands, Component, Entity, Handle, IntoScheduleConfigs, Plugin,
+    Query, Res, ResMut, Resource, Shader, With,
+};
+use bevy::render::extract_component::{ExtractComponent, ExtractComponentPlugin};
+use bevy::render::mesh::allocator::MeshAllocator;
+use bevy::render::mesh::{RenderMesh, RenderMeshBufferInfo};
+use bevy::render::render_asset::RenderAssets;
+use bevy::render::render_phase::{
+    AddRenderCommand, DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand,
+    RenderCommandResult, SetItemPipeline, TrackedRenderPass, ViewSortedRenderPhases,
+};
+use bevy::render::render_resource::{
+    Buffer, BufferInitDescriptor, BufferUsages, PipelineCache, RenderPipelineDescriptor,
+    SpecializedMeshPipeline, SpecializedMeshPipelineError, SpecializedMeshPipelines,
+    VertexAttribute, VertexFormat, VertexStepMode,
+};
+use bevy::render::renderer::{RenderDevice, RenderQueue};
+use bevy::render::sync_world::MainEntity;
+use bevy::render::view::{ExtractedView, Msaa};
+use bevy::render::{Render, RenderApp, RenderStartup, RenderSystems};
+
+const SHADER_PATH: &str = "embedded://geo_bevy/instanced_points.wgsl";
+
+/// Draws `InstancedPoints` layers.
+pub struct InstancedPointsPlugin;
+
+impl Plugin for InstancedPointsPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "instanced_points.wgsl");
+        app.add_plugins(ExtractComponentPlugin::<InstancedPoints>::default());
+        app.sub_app_mut(RenderApp)
+            .add_render_command::<Transparent3d, DrawInstancedPoints>()
+            .init_resource::<SpecializedMeshPipelines<InstancedPointsPipeline>>()
+            .add_systems(RenderStartup, init_pipeline)
+            .add_systems(
+                Render,
+                (
+                    queue_instanced_points.in_set(RenderSystems::QueueMeshes),
+                    prepare_instance_buffers.in_set(RenderSystems::PrepareResources),
+                ),
+            );
+    }
+}
+
+/// A point layer drawn in a single call: the entity's `Mesh3d` is the marker, drawn once per
+/// instance, scaled, rotated about z and moved to the instance's position in world space. The
+/// entity's transform is ignored. Changing `instances` rewrites the instance buffer.
+#[derive(Component, Clone, Debug, Default)]
+#[require(NoFrustumCulling)]
+pub struct InstancedPoints {
+    pub instances: Vec<PointInstance>,
+}
+
+impl InstancedPoints {
+    pub fn new(instances: Vec<PointInstance>) -> Self {
+        InstancedPoints { instances }
+    }
+}
+
+impl ExtractComponent for InstancedPoints {
+    type QueryData = &'static InstancedPoints;
+    // Unchanged layers keep the copy extracted last time, and their instance buffer.
+    type QueryFilter = Changed<InstancedPoints>;
+    type Out = Self;
+
+    fn extract_component(item: QueryItem<'_, '_, Self::QueryData>) -> Option<Self> {
+        Some(item.clone())
+    }
+}
+
+#[derive(Component)]
+struct InstanceBuffer {
+    buffer: Buffer,
+    /// Instances the buffer has room for.
+    capacity: usize,
+    length: usize,
+}
+
+fn instance_bytes(instances: &[PointInstance]) -> Vec<u8> {
+    instances
+        .iter()
+        .flat_map(|instance| {
+            instance
+                .position
+                .into_iter()
+                .chain([instance.size])
+                .chain(instance.color)
+                .chain([instance.rotation])
+        })
+        .flat_map(f32::to_ne_bytes)
+        .collect()
+}
+
+/// Writes the instances of each changed layer over its buffer, only allocating a new one when they
+/// no longer fit.
+fn prepare_instance_buffers(
+    mut commands: Commands,
+    mut layers: Query<
+        (Entity, &InstancedPoints, Option<&mut InstanceBuffer>),
+        Changed<InstancedPoints>,
+    >,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    for (entity, layer, buffer) in &mut layers {
+        let bytes = instance_bytes(&layer.instances);
+        match buffer {
+            Some(mut buffer) if buffer.capacity >= layer.instances.len() => {
+                render_queue.write_buffer(&buffer.buffer, 0, &bytes);
+                buffer.length = layer.instances.len();
+            }
+            _ => {
+                let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+                    label: Some("geo_bevy instanced points"),
+                    contents: &bytes,
+                    usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                });
+                commands.entity(entity).insert(InstanceBuffer {
+                    buffer,
+                    capacity: layer.instances.len(),
+                    length: layer.instances.len(),
+                });
+            }
+        }
+    }
+}
+
+#[derive(Resource)]
+struct InstancedPointsPipeline {
+    shader: Handle<Shader>,
+    mesh_pipeline: MeshPipeline,
+}
+
+fn init_pipeline(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mesh_pipeline: Res<MeshPipeline>,
+) {
+    commands.insert_resource(InstancedPointsPipeline {
+        shader: asset_server.load(SHADER_PATH),
+        mesh_pipeline: mesh_pipeline.clone(),
+    });
+}
+
+impl SpecializedMeshPipeline for InstancedPointsPipeline {
+    type Key = MeshPipelineKey;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayoutRef,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let mut descriptor = self.mesh_pipeline.specialize(key, layout)?;
+        descriptor.vertex.shader = self.shader.clone();
+        // Locations after those of the mesh attributes the mesh pipeline knows about.
+        let attributes = [
+            VertexFormat::Float32x3,
+            VertexFormat::Float32,
+            VertexFormat::Float32x4,
+            VertexFormat::Float32,
+        ];
+        let mut offset = 0;
+        descriptor.vertex.buffers.push(VertexBufferLayout {
+            array_stride: size_of::<PointInstance>() as u64,
+            step_mode: VertexStepMode::Instance,
+            attributes: attributes
+                .into_iter()
+                .zip(8..)
+                .map(|(format, shader_location)| {
+                    let attribute = VertexAttribute {
+                        format,
+                        offset,
+                        shader_location,
+                    };
+                    offset += format.size();
+                    attribute
+                })
+                .collect(),
+        });
+        if let Some(fragment) = descriptor.fragment.as_mut() {
+            fragment.shader = self.shader.clone();
+        }
+        Ok(descriptor)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn queue_instanced_points(
+    draw_functions: Res<DrawFunctions<Transparent3d>>,
+    pipeline: Res<InstancedPointsPipeline>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<InstancedPointsPipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    meshes: Res<RenderAssets<RenderMesh>>,
+    render_mesh_instances: Res<RenderMeshInstances>,
+    layers: Query<(Entity, &MainEntity), With<InstancedPoints>>,
+    mut phases: ResMut<ViewSortedRenderPhases<Transparent3d>>,
+    views: Query<(&ExtractedView, &Msaa)>,
+) {
+    let draw_function = draw_functions.read().id::<DrawInstancedPoints>();
+    for (view, msaa) in &views {
+        let Some(phase) = phases.get_mut(&view.retained_view_entity) else {
+            continue;
+        };
+        let view_key = MeshPipelineKey::from_msaa_samples(msaa.samples())
+            | MeshPipelineKey::from_hdr(view.hdr);
+        let rangefinder = view.rangefinder3d();
+        for (entity, main_entity) in &layers {
+            let Some(mesh_instance) = render_mesh_instances.render_mesh_queue_data(*main_entity)
+            else {
+                continue;
+            };
+            let Some(mesh) = meshes.get(mesh_instance.mesh_asset_id) else {
+                continue;
+            };
+            let key =
+                view_key | MeshPipelineKey::from_primitive_topology(mesh.primitive_topology());
+            let Ok(pipeline) = pipelines.specialize(&pipeline_cache, &pipeline, key, &mesh.layout)
+            else {
+                continue;
+            };
+            phase.add(Transparent3d {
+                entity: (entity, *main_entity),
+                pipeline,
+                draw_function,
+                distance: rangefinder.distance(&mesh_instance.center),
+                batch_range: 0..1,
+                extra_index: PhaseItemExtraIndex::None,
+                indexed: true,
+            });
+        }
+    }
+}
+
+type DrawInstancedPoints = (
+    SetItemPipeline,
+    SetMeshViewBindGroup<0>,
+    SetMeshViewBindingArrayBindGroup<1>,
+    SetMeshBindGroup<2>,
+    DrawMeshInstanced,
+);
+
+struct DrawMeshInstanced;
+
+impl<P: PhaseItem> RenderCommand<P> for DrawMeshInstanced {
+    type Param = (
+        SRes<RenderAssets<RenderMesh>>,
+        SRes<RenderMeshInstances>,
+        SRes<MeshAllocator>,
+    );
+    type ViewQuery = ();
+    type ItemQuery = Read<InstanceBuffer>;
+
+    fn render<'w>(
+        item: &P,
+        _view: (),
+        instance_buffer: Option<&'w InstanceBuffer>,
+        (meshes, render_mesh_instances, mesh_allocator): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let mesh_allocator = mesh_allocator.into_inner();
+        let Some(mesh_instance) = render_mesh_instances.render_mesh_queue_data(item.main_entity())
+        else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(gpu_mesh) = meshes.into_inner().get(mesh_instance.mesh_asset_id) else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(instance_buffer) = instance_buffer else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(vertex_buffer_slice) =
+            mesh_allocator.mesh_vertex_slice(&mesh_instance.mesh_asset_id)
+        else {
+            return RenderCommandResult::Skip;
+        };
+
+        let instances = 0..instance_buffer.length as u32;
+        pass.set_vertex_buffer(0, vertex_buffer_slice.buffer.slice(..));
+        pass.set_vertex_buffer(1, instance_buffer.buffer.slice(..));
+        match &gpu_mesh.buffer_info {
+            RenderMeshBufferInfo::Indexed {
+                index_format,
+                count,
+            } => {
+                let Some(index_buffer_slice) =
+                    mesh_allocator.mesh_index_slice(&mesh_instance.mesh_asset_id)
+                else {
+                    return RenderCommandResult::Skip;
+                };
+                pass.set_index_buffer(index_buffer_slice.buffer.slice(..), *index_format);
+                pass.draw_indexed(
+                    index_buffer_slice.range.start..(index_buffer_slice.range.start + count),
+                    vertex_buffer_slice.range.start as i32,
+                    instances,
+                );
+            }
+            RenderMeshBufferInfo::NonIndexed => {
+                pass.draw(vertex_buffer_slice.range, instances);
+            }
+        }
+        RenderCommandResult::Success
+    }
+}
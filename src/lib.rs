@@ -2,27 +2,87 @@
 #![recursion_limit = "256"]
 
 use bevy::prelude::{info_span, Mesh};
-use build_mesh::BuildMesh;
 use geo_traits::*;
-use line_string::LineStringMeshBuilder;
 use num_traits::ToPrimitive;
-use polygon::PolygonMeshBuilder;
-use std::iter;
+use std::sync::Arc;
 
-pub use point::SpritePosition;
-pub use polygon::PolygonMesh;
+pub use build_mesh::{BuildBevyMeshesContext, BuildMesh};
+pub use dash::{DashPattern, DashUnits};
+pub use height::{GeoTransform, HeightSource, HeightmapGrid, ImageHeightmap};
+#[cfg(feature = "material")]
+pub use instancing::{InstancedPoints, InstancedPointsPlugin};
+pub use line_string::{
+    LineStringMeshBuilder, ATTRIBUTE_LINE_DISTANCE, ATTRIBUTE_LINE_EXTRUSION,
+    ATTRIBUTE_LINE_PROGRESS,
+};
+#[cfg(feature = "maplibre")]
+pub use maplibre::{parse_geojson, GeoJsonFeature, LayerKind, MapStyle, Source, StyleLayer};
+pub use marker::{MarkerMeshBuilder, MarkerShape, Markers};
+#[cfg(feature = "material")]
+pub use material::{
+    BoundaryGradient, BoundaryGradientMaterial, BoundaryGradientPlugin, DashedLine,
+    DashedLineMaterial, DashedLinePlugin, LineReveal, LineRevealMaterial, LineRevealPlugin,
+    ScreenSpaceLine, ScreenSpaceLineMaterial, ScreenSpaceLinePlugin, StripRestart,
+};
+pub use options::{IndexWidth, LineStyle, MeshOptions, OutlineGrouping, PolygonMode, RingClosure};
+pub use pattern::{FillPattern, PatternKind};
+pub use point::{PointInstance, PointMeshBuilder, SpritePosition};
+pub use polygon::{
+    MultiPolygonMesh, PolygonIndexRanges, PolygonMesh, PolygonMeshBuilder, PolygonMeshParts,
+    ATTRIBUTE_BOUNDARY_DISTANCE,
+};
+pub use style::{
+    Expression, FeatureStyle, Interpolate, Properties, Style, Value, ATTRIBUTE_LINE_WIDTH,
+};
+pub use terrain::{terrain_to_mesh, terrain_to_mesh_with_options};
+pub use tree::GeometryMeshTree;
+#[cfg(feature = "spade")]
+pub use triangulate::ConstrainedDelaunayTriangulator;
+#[cfg(feature = "lyon")]
+pub use triangulate::LyonTriangulator;
+pub use triangulate::{
+    EarcutrTriangulator, Refinement, Triangulation, TriangulationInput, Triangulator,
+};
+pub use validation::{
+    repair_polygon, validate_polygon, Ring, Validation, ValidationIssue, ValidationReport,
+};
 
+mod boundary;
 mod build_mesh;
+mod chunks;
+mod dash;
+mod height;
+#[cfg(feature = "material")]
+mod instancing;
 mod line_string;
+#[cfg(feature = "maplibre")]
+mod maplibre;
+mod marker;
+#[cfg(feature = "material")]
+mod material;
+mod options;
+mod pattern;
 mod point;
 mod polygon;
+mod style;
+mod terrain;
+mod tree;
+mod triangulate;
+mod validation;
 
 pub fn line_to_mesh<L: LineTrait>(line: L) -> Result<Mesh, Error>
 where
     L::T: ToPrimitive,
 {
-    let mut mesh_builder = LineStringMeshBuilder::new();
-    mesh_builder.add_coords(iter::once(line.start()).chain(iter::once(line.end())))?;
+    line_to_mesh_with_options(line, MeshOptions::default())
+}
+
+pub fn line_to_mesh_with_options<L: LineTrait>(line: L, options: MeshOptions) -> Result<Mesh, Error>
+where
+    L::T: ToPrimitive,
+{
+    let mut mesh_builder = LineStringMeshBuilder::new().with_options(options);
+    mesh_builder.add_line(&line)?;
     mesh_builder.try_into()
 }
 
@@ -30,8 +90,18 @@ pub fn line_string_to_mesh<LS: LineStringTrait>(line_string: LS) -> Result<Mesh,
 where
     LS::T: ToPrimitive,
 {
-    let mut mesh_builder = LineStringMeshBuilder::new();
-    mesh_builder.add_coords(line_string.coords())?;
+    line_string_to_mesh_with_options(line_string, MeshOptions::default())
+}
+
+pub fn line_string_to_mesh_with_options<LS: LineStringTrait>(
+    line_string: LS,
+    options: MeshOptions,
+) -> Result<Mesh, Error>
+where
+    LS::T: ToPrimitive,
+{
+    let mut mesh_builder = LineStringMeshBuilder::new().with_options(options);
+    mesh_builder.add_line_string(&line_string)?;
     mesh_builder.try_into()
 }
 
@@ -41,10 +111,31 @@ pub fn multi_line_string_to_mesh<MLS: MultiLineStringTrait>(
 where
     MLS::T: ToPrimitive,
 {
+    multi_line_string_to_mesh_with_options(multi_line_string, MeshOptions::default())
+}
+
+pub fn multi_line_string_to_mesh_with_options<MLS: MultiLineStringTrait>(
+    multi_line_string: MLS,
+    options: MeshOptions,
+) -> Result<Vec<Mesh>, Error>
+where
+    MLS::T: ToPrimitive,
+{
+    if options.merge_line_strings {
+        let mut mesh_builder = LineStringMeshBuilder::new().with_options(options);
+        for line_string in multi_line_string.line_strings() {
+            mesh_builder.add_line_string(&line_string)?;
+        }
+        return mesh_builder.build_meshes();
+    }
+
     let mut line_string_meshes = Vec::with_capacity(multi_line_string.num_line_strings());
 
     for line_string in multi_line_string.line_strings() {
-        line_string_meshes.push(line_string_to_mesh(line_string)?);
+        line_string_meshes.push(line_string_to_mesh_with_options(
+            line_string,
+            options.clone(),
+        )?);
     }
 
     Ok(line_string_meshes)
@@ -53,86 +144,302 @@ where
 pub fn polygon_to_mesh<Scalar: geo_types::CoordFloat>(
     polygon: impl PolygonTrait<T = Scalar>,
 ) -> Result<PolygonMesh, Error> {
-    let mut mesh_builder = PolygonMeshBuilder::new();
+    polygon_to_mesh_with_options(polygon, MeshOptions::default())?.try_into()
+}
+
+pub fn polygon_to_mesh_with_options<Scalar: geo_types::CoordFloat>(
+    polygon: impl PolygonTrait<T = Scalar>,
+    options: MeshOptions,
+) -> Result<PolygonMeshParts, Error> {
+    let mut mesh_builder = PolygonMeshBuilder::new().with_options(options);
+    mesh_builder.add_polygon(&polygon)?;
+    mesh_builder.try_into()
+}
+
+/// Like `polygon_to_mesh`, but triangulates the fill with `triangulator` instead of earcutr.
+pub fn polygon_to_mesh_with_triangulator<Scalar: geo_types::CoordFloat>(
+    polygon: impl PolygonTrait<T = Scalar>,
+    triangulator: impl Triangulator<Scalar> + 'static,
+) -> Result<PolygonMesh, Error> {
+    let mut mesh_builder = PolygonMeshBuilder::new().with_triangulator(triangulator);
+    mesh_builder.add_polygon(&polygon)?;
+    mesh_builder.try_into()
+}
+
+/// Like `polygon_to_mesh`, but subdivides the fill so no triangle exceeds `refinement`'s limits.
+pub fn polygon_to_mesh_with_refinement<Scalar: geo_types::CoordFloat>(
+    polygon: impl PolygonTrait<T = Scalar>,
+    refinement: Refinement,
+) -> Result<PolygonMesh, Error> {
+    let mut mesh_builder = PolygonMeshBuilder::new().with_refinement(refinement);
     mesh_builder.add_polygon(&polygon)?;
     mesh_builder.try_into()
 }
 
+/// Like `polygon_to_mesh`, but checks or repairs `polygon` first and returns what was found.
+pub fn polygon_to_mesh_with_validation<Scalar: geo_types::CoordFloat>(
+    polygon: impl PolygonTrait<T = Scalar>,
+    validation: Validation,
+) -> Result<(PolygonMesh, ValidationReport), Error> {
+    let mut mesh_builder = PolygonMeshBuilder::new().with_validation(validation);
+    let report = mesh_builder.add_polygon_with_report(&polygon)?;
+    Ok((mesh_builder.try_into()?, report))
+}
+
 pub fn multi_polygon_to_mesh<Scalar: geo_types::CoordFloat>(
     multi_polygon: impl MultiPolygonTrait<T = Scalar>,
 ) -> Result<Vec<PolygonMesh>, Error> {
+    multi_polygon_to_mesh_with_options(multi_polygon, MeshOptions::default())?
+        .into_iter()
+        .map(PolygonMesh::try_from)
+        .collect()
+}
+
+pub fn multi_polygon_to_mesh_with_options<Scalar: geo_types::CoordFloat>(
+    multi_polygon: impl MultiPolygonTrait<T = Scalar>,
+    options: MeshOptions,
+) -> Result<Vec<PolygonMeshParts>, Error> {
     let polygons = multi_polygon.polygons();
     let mut polygon_meshes = Vec::with_capacity(polygons.len());
     for polygon in polygons {
-        polygon_meshes.push(polygon_to_mesh(polygon)?);
+        polygon_meshes.push(polygon_to_mesh_with_options(polygon, options.clone())?);
     }
 
     Ok(polygon_meshes)
 }
 
+/// Like `multi_polygon_to_mesh`, but builds a single fill mesh and a single outline mesh, with
+/// the index ranges of each polygon.
+pub fn multi_polygon_to_merged_mesh<Scalar: geo_types::CoordFloat>(
+    multi_polygon: impl MultiPolygonTrait<T = Scalar>,
+) -> Result<MultiPolygonMesh, Error> {
+    multi_polygon_to_merged_mesh_with_options(multi_polygon, MeshOptions::default())
+}
+
+/// `options.outline_grouping` is ignored, as outlines are always combined.
+pub fn multi_polygon_to_merged_mesh_with_options<Scalar: geo_types::CoordFloat>(
+    multi_polygon: impl MultiPolygonTrait<T = Scalar>,
+    options: MeshOptions,
+) -> Result<MultiPolygonMesh, Error> {
+    let mut mesh_builder = PolygonMeshBuilder::new().with_options(MeshOptions {
+        outline_grouping: OutlineGrouping::Combined,
+        ..options
+    });
+    let mut ranges = Vec::with_capacity(multi_polygon.num_polygons());
+    for polygon in multi_polygon.polygons() {
+        ranges.push(mesh_builder.add_polygon_with_ranges(&polygon)?);
+    }
+
+    let PolygonMeshParts {
+        mesh, outline_mesh, ..
+    } = mesh_builder.try_into()?;
+    Ok(MultiPolygonMesh {
+        mesh,
+        outline_mesh,
+        ranges,
+    })
+}
+
 pub fn rect_to_mesh<Scalar: geo_types::CoordFloat>(
     rect: impl RectTrait<T = Scalar>,
 ) -> Result<PolygonMesh, Error> {
-    let mut mesh_builder = PolygonMeshBuilder::default();
-    mesh_builder.add_polygon_from_exterior_coords(
-        [
-            (rect.min().x(), rect.min().y()),
-            (rect.min().x(), rect.max().y()),
-            (rect.max().x(), rect.max().y()),
-            (rect.max().x(), rect.min().y()),
-            (rect.min().x(), rect.min().y()),
-        ]
-        .into_iter(),
-    )?;
-    PolygonMesh::try_from(mesh_builder)
+    rect_to_mesh_with_options(rect, MeshOptions::default())?.try_into()
+}
+
+pub fn rect_to_mesh_with_options<Scalar: geo_types::CoordFloat>(
+    rect: impl RectTrait<T = Scalar>,
+    options: MeshOptions,
+) -> Result<PolygonMeshParts, Error> {
+    let mut mesh_builder = PolygonMeshBuilder::new().with_options(options);
+    mesh_builder.add_rect(&rect)?;
+    PolygonMeshParts::try_from(mesh_builder)
 }
 
 pub fn triangle_to_mesh<Scalar: geo_types::CoordFloat>(
     triangle: impl TriangleTrait<T = Scalar>,
 ) -> Result<PolygonMesh, Error> {
-    let mut mesh_builder = PolygonMeshBuilder::default();
-    mesh_builder.add_polygon_from_exterior_coords(
-        [
-            (triangle.first().x(), triangle.first().y()),
-            (triangle.second().x(), triangle.second().y()),
-            (triangle.third().x(), triangle.third().y()),
-            (triangle.first().x(), triangle.first().y()),
-        ]
-        .into_iter(),
-    )?;
-    PolygonMesh::try_from(mesh_builder)
+    triangle_to_mesh_with_options(triangle, MeshOptions::default())?.try_into()
+}
+
+pub fn triangle_to_mesh_with_options<Scalar: geo_types::CoordFloat>(
+    triangle: impl TriangleTrait<T = Scalar>,
+    options: MeshOptions,
+) -> Result<PolygonMeshParts, Error> {
+    let mut mesh_builder = PolygonMeshBuilder::new().with_options(options);
+    mesh_builder.add_triangle(&triangle)?;
+    PolygonMeshParts::try_from(mesh_builder)
 }
 
 pub fn geometry_to_mesh<Scalar: geo_types::CoordFloat>(
     geometry: impl GeometryTrait<T = Scalar>,
 ) -> Result<GeometryMesh, Error> {
-    let mut ctx = build_mesh::BuildBevyMeshesContext::default();
+    geometry_to_mesh_with_triangulator(geometry, EarcutrTriangulator)
+}
 
-    info_span!("Populating Bevy mesh builder")
-        .in_scope(|| build_mesh::populate_geometry_mesh_builders(&geometry, &mut ctx))?;
+pub fn geometry_to_mesh_with_options<Scalar: geo_types::CoordFloat>(
+    geometry: impl GeometryTrait<T = Scalar>,
+    options: MeshOptions,
+) -> Result<GeometryMesh, Error> {
+    build_geometry_mesh(
+        geometry,
+        BuildBevyMeshesContext::new().with_options(options),
+    )
+}
 
-    info_span!("Building Bevy meshes").in_scope(|| {
-        [
-            ctx.point_mesh_builder.build(),
-            ctx.line_string_mesh_builder.build(),
-            ctx.polygon_mesh_builder.build(),
-        ]
-        .into_iter()
-        .find(|prepared_mesh| prepared_mesh.is_ok())
-        .unwrap_or(Err(Error::CouldNotBuildMesh))
-    })
+/// Like `geometry_to_mesh`, but drapes line strings and polygons onto `height_source`.
+pub fn geometry_to_mesh_with_height_source<Scalar: geo_types::CoordFloat>(
+    geometry: impl GeometryTrait<T = Scalar>,
+    height_source: Arc<dyn HeightSource>,
+) -> Result<GeometryMesh, Error> {
+    let ctx = BuildBevyMeshesContext {
+        line_string_mesh_builder: LineStringMeshBuilder::new()
+            .with_height_source(height_source.clone()),
+        polygon_mesh_builder: PolygonMeshBuilder::new().with_height_source(height_source),
+        ..Default::default()
+    };
+    build_geometry_mesh(geometry, ctx)
+}
+
+/// Like `geometry_to_mesh`, but triangulates polygon fills with `triangulator`.
+pub fn geometry_to_mesh_with_triangulator<Scalar: geo_types::CoordFloat>(
+    geometry: impl GeometryTrait<T = Scalar>,
+    triangulator: impl Triangulator<Scalar> + 'static,
+) -> Result<GeometryMesh, Error> {
+    let ctx = BuildBevyMeshesContext {
+        polygon_mesh_builder: PolygonMeshBuilder::new().with_triangulator(triangulator),
+        ..Default::default()
+    };
+    build_geometry_mesh(geometry, ctx)
+}
+
+/// Like `geometry_to_mesh_with_options`, but splits the result into several meshes where one
+/// would exceed `MeshOptions::index_width`.
+pub fn geometry_to_meshes_with_options<Scalar: geo_types::CoordFloat>(
+    geometry: impl GeometryTrait<T = Scalar>,
+    options: MeshOptions,
+) -> Result<Vec<GeometryMesh>, Error> {
+    let mut ctx = BuildBevyMeshesContext::new().with_options(options);
+    info_span!("Populating Bevy mesh builder").in_scope(|| ctx.add_geometry(&geometry))?;
+
+    info_span!("Building Bevy meshes").in_scope(|| ctx.build_meshes())
+}
+
+/// Meshes features styled by `style`, e.g. for a choropleth: each feature's vertices carry
+/// the colors, widths and z order resolved from its properties. Features of a kind share
+/// meshes, which are split where one would exceed `MeshOptions::index_width`.
+pub fn features_to_meshes<Scalar, G, P>(
+    features: impl IntoIterator<Item = (G, P)>,
+    style: &Style,
+) -> Result<Vec<GeometryMesh>, Error>
+where
+    Scalar: geo_types::CoordFloat,
+    G: GeometryTrait<T = Scalar>,
+    P: Properties,
+{
+    features_to_meshes_with_options(features, style, MeshOptions::default())
+}
+
+pub fn features_to_meshes_with_options<Scalar, G, P>(
+    features: impl IntoIterator<Item = (G, P)>,
+    style: &Style,
+    options: MeshOptions,
+) -> Result<Vec<GeometryMesh>, Error>
+where
+    Scalar: geo_types::CoordFloat,
+    G: GeometryTrait<T = Scalar>,
+    P: Properties,
+{
+    let mut ctx = BuildBevyMeshesContext::new().with_options(options);
+    info_span!("Populating Bevy mesh builder").in_scope(|| {
+        features
+            .into_iter()
+            .try_for_each(|(geometry, properties)| ctx.add_feature(&geometry, &properties, style))
+    })?;
+
+    info_span!("Building Bevy meshes").in_scope(|| ctx.build_meshes())
+}
+
+/// Like `geometry_to_mesh`, but builds each member of geometry collections separately and
+/// returns them in a tree mirroring the collections.
+pub fn geometry_to_mesh_tree<Scalar: geo_types::CoordFloat>(
+    geometry: impl GeometryTrait<T = Scalar>,
+) -> Result<GeometryMeshTree, Error> {
+    geometry_to_mesh_tree_with_options(geometry, MeshOptions::default())
+}
+
+pub fn geometry_to_mesh_tree_with_options<Scalar: geo_types::CoordFloat>(
+    geometry: impl GeometryTrait<T = Scalar>,
+    options: MeshOptions,
+) -> Result<GeometryMeshTree, Error> {
+    let mut ctx = BuildBevyMeshesContext::new().with_options(options);
+    info_span!("Building Bevy mesh tree")
+        .in_scope(|| tree::build_tree(&geometry, &mut ctx, &mut Vec::new()))
+}
+
+fn build_geometry_mesh<Scalar: geo_types::CoordFloat>(
+    geometry: impl GeometryTrait<T = Scalar>,
+    mut ctx: BuildBevyMeshesContext<Scalar>,
+) -> Result<GeometryMesh, Error> {
+    info_span!("Populating Bevy mesh builder").in_scope(|| ctx.add_geometry(&geometry))?;
+
+    info_span!("Building Bevy meshes").in_scope(|| ctx.build())
 }
 
+#[allow(clippy::large_enum_variant)]
 pub enum GeometryMesh {
     Point(Vec<SpritePosition>),
     LineString(Mesh),
     Polygon(polygon::PolygonMesh),
+    /// Polygons built with a `MeshOptions::polygon_mode` or `MeshOptions::outline_grouping` that
+    /// doesn't give a `PolygonMesh`.
+    PolygonParts(polygon::PolygonMeshParts),
 }
 
 #[derive(Debug)]
 pub enum Error {
     CouldNotBuildMesh,
     CouldNotConvertToF32,
+    CouldNotConvertToF64,
     EmptyGeometry,
-    BevyEarcutr(bevy_earcutr::Error),
+    InvalidGeometry(ValidationReport),
+    /// Earcutr failed to triangulate a fill. Replaces `BevyEarcutr`, whose `bevy_earcutr::Error`
+    /// went away with the `bevy-earcutr` dependency.
+    Earcutr(earcutr::Error),
+    #[cfg(feature = "spade")]
+    Spade(spade::InsertionError),
+    #[cfg(feature = "lyon")]
+    Lyon(lyon_tessellation::TessellationError),
+    UnsupportedImageFormat(bevy::render::render_resource::TextureFormat),
+    /// A `HeightmapGrid` given a number of values other than `width * height`.
+    HeightmapSizeMismatch {
+        width: usize,
+        height: usize,
+        num_values: usize,
+    },
+    /// The geometries need more vertices than `MeshOptions::index_width` can address in one
+    /// mesh. `BuildMesh::build_meshes` splits them instead.
+    IndexOverflow,
+    UnsupportedTopology(bevy::render::render_resource::PrimitiveTopology),
+    /// `Refinement` limits that aren't valid, or that a fill can't reach within
+    /// `Refinement::max_vertices`.
+    InvalidRefinement(Refinement),
+    /// A `DashPattern` that isn't valid, or that a material can't draw.
+    InvalidDashPattern(DashPattern),
+    /// A `FillPattern` that isn't valid.
+    InvalidFillPattern(FillPattern),
+    /// `Markers` that aren't valid, or that would place more than `Markers::max_markers`.
+    InvalidMarkers(Markers),
+    #[cfg(feature = "maplibre")]
+    Json(serde_json::Error),
+    /// A MapLibre style layer, property or expression that is malformed or outside the
+    /// supported subset, as JSON.
+    #[cfg(feature = "maplibre")]
+    InvalidStyle(String),
+    /// A MapLibre style source that layers draw from but that isn't GeoJSON, e.g. a vector tile
+    /// source, by name.
+    #[cfg(feature = "maplibre")]
+    UnsupportedSource(String),
+    /// GeoJSON that isn't a feature collection, feature or geometry, as JSON.
+    #[cfg(feature = "maplibre")]
+    InvalidGeoJson(String),
 }
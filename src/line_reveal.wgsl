@@ -0,0 +1,41 @@
+#import bevy_pbr::mesh_functions::{get_world_from_local, mesh_position_local_to_clip}
+
+struct LineReveal {
+    color: vec4<f32>,
+    progress: f32,
+    feather: f32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> reveal: LineReveal;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) line_progress: f32,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) line_progress: f32,
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    out.clip_position = mesh_position_local_to_clip(
+        get_world_from_local(vertex.instance_index),
+        vec4<f32>(vertex.position, 1.0),
+    );
+    out.line_progress = vertex.line_progress;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let ahead = in.line_progress - reveal.progress;
+    if ahead > reveal.feather {
+        discard;
+    }
+    let fade = 1.0 - clamp(ahead / max(reveal.feather, 1e-6), 0.0, 1.0);
+    return vec4<f32>(reveal.color.rgb, reveal.color.a * fade);
+}
@@ -1,13 +1,58 @@
+use crate::build_mesh::Counts;
+use crate::chunks::Chunks;
+use crate::dash::Dasher;
+use crate::height::HeightSource;
+use crate::options::{LineStyle, MeshOptions, RingClosure, STRIP_RESTART};
+use crate::style::{FeatureStyle, StyleSpans, ATTRIBUTE_LINE_WIDTH};
+use bevy::color::ColorToComponents;
+use bevy::mesh::{MeshVertexAttribute, VertexFormat};
 use bevy::prelude::Mesh;
-use geo_traits::CoordTrait;
+use bevy::render::render_resource::PrimitiveTopology;
+use geo_traits::{CoordTrait, GeometryTrait, LineStringTrait, LineTrait};
 use num_traits::cast::ToPrimitive;
+use std::iter;
+use std::sync::Arc;
 
 type Vertex = [f32; 3]; // [x, y, z]
 
+/// Distance of each vertex from the start of its line string or ring, in geo units. Added with
+/// `MeshOptions::line_distances` or when dashing in screen units.
+pub const ATTRIBUTE_LINE_DISTANCE: MeshVertexAttribute = MeshVertexAttribute::new(
+    "Vertex_LineDistance",
+    0x6765_6f62_6576_7901,
+    VertexFormat::Float32,
+);
+
+/// `ATTRIBUTE_LINE_DISTANCE` divided by the length of the line string or ring, from 0 at its
+/// start to 1 at its end. Added along with it.
+pub const ATTRIBUTE_LINE_PROGRESS: MeshVertexAttribute = MeshVertexAttribute::new(
+    "Vertex_LineProgress",
+    0x6765_6f62_6576_7902,
+    VertexFormat::Float32,
+);
+
+/// Direction in which a `LineStyle::ScreenSpace` vertex is pushed off the line, as a unit
+/// vector in the xy plane, or zero for vertices that stay on it.
+pub const ATTRIBUTE_LINE_EXTRUSION: MeshVertexAttribute = MeshVertexAttribute::new(
+    "Vertex_LineExtrusion",
+    0x6765_6f62_6576_7903,
+    VertexFormat::Float32x2,
+);
+
 #[derive(Default)]
 pub struct LineStringMeshBuilder {
     vertices: Vec<Vertex>,
     indices: Vec<u32>,
+    distances: Vec<f32>,
+    progress: Vec<f32>,
+    styles: StyleSpans,
+    chunks: Chunks,
+    height_source: Option<Arc<dyn HeightSource>>,
+    options: MeshOptions,
+    dasher: Option<Dasher>,
+    /// Last point of the current line string, dashed or not, and its distance along it.
+    pen: [f64; 2],
+    travelled: f64,
 }
 
 impl LineStringMeshBuilder {
@@ -15,75 +60,644 @@ impl LineStringMeshBuilder {
         Self::default()
     }
 
-    /// Call for `add_earcutr_input` for each polygon you want to add to the mesh.
-    /// Logs error if self.vertices.len() + linestring coords > u32::MAX (4_294_967_295).
+    pub fn with_options(mut self, options: MeshOptions) -> Self {
+        self.dasher = options.dash.as_ref().and_then(Dasher::new);
+        self.options = options;
+        self
+    }
+
+    /// Drapes vertices onto `height_source`, resampling segments at its resolution.
+    pub fn with_height_source(mut self, height_source: Arc<dyn HeightSource>) -> Self {
+        self.height_source = Some(height_source);
+        self
+    }
+
+    /// Styles line strings added from now on. Once a style is set, meshes get
+    /// `Mesh::ATTRIBUTE_COLOR` and `ATTRIBUTE_LINE_WIDTH`, with the default style for line
+    /// strings added before.
+    pub fn set_style(&mut self, style: &FeatureStyle) {
+        self.styles.start(self.vertices.len(), *style);
+    }
+
+    pub fn add_line_string<LS: LineStringTrait>(
+        &mut self,
+        line_string: &LS,
+    ) -> Result<(), crate::Error>
+    where
+        LS::T: ToPrimitive,
+    {
+        self.add_coords(line_string.coords())
+    }
+
+    pub fn add_line<L: LineTrait>(&mut self, line: &L) -> Result<(), crate::Error>
+    where
+        L::T: ToPrimitive,
+    {
+        self.add_coords(iter::once(line.start()).chain(iter::once(line.end())))
+    }
+
+    /// Adds a line string. When the current mesh is full, the line string continues in a new
+    /// one, starting again from its last vertex.
     pub fn add_coords<I, C>(&mut self, coords: I) -> Result<(), crate::Error>
     where
         I: Iterator<Item = C>,
         C: CoordTrait,
         C::T: ToPrimitive,
     {
-        let index_base = self.vertices.len();
-
-        // Reserve space for vertices and indices
-        self.vertices.reserve(self.vertices.len());
-        self.indices.reserve(self.indices.len() * 2);
-
-        let mut last_index = None;
-        for (i, coord) in coords.enumerate() {
-            self.vertices.push([
-                coord
-                    .x()
-                    .to_f32()
-                    .ok_or(crate::Error::CouldNotConvertToF32)?,
-                coord
-                    .y()
-                    .to_f32()
-                    .ok_or(crate::Error::CouldNotConvertToF32)?,
-                0.0,
-            ]);
-
-            if let Some(last) = last_index {
-                self.indices.push(last as u32);
-                self.indices.push((index_base + i) as u32);
+        self.with_progress(|builder| builder.push_coords(coords))
+    }
+
+    /// Adds a polygon ring, closed according to `MeshOptions::ring_closure` whether or not its
+    /// first coordinate is repeated at the end.
+    pub fn add_ring<I, C>(&mut self, coords: I) -> Result<(), crate::Error>
+    where
+        I: Iterator<Item = C>,
+        C: CoordTrait,
+        C::T: ToPrimitive,
+    {
+        self.with_progress(|builder| builder.push_ring(coords))
+    }
+
+    fn push_coords<I, C>(&mut self, coords: I) -> Result<(), crate::Error>
+    where
+        I: Iterator<Item = C>,
+        C: CoordTrait,
+        C::T: ToPrimitive,
+    {
+        self.reserve(coords.size_hint().0, 1);
+
+        let mut previous = None;
+        for coord in coords {
+            let xy = coord_to_xy(coord)?;
+            if let Some(previous) = previous {
+                self.push_resampled(previous, xy)?;
+            }
+            self.push_point(xy, previous.is_none())?;
+            previous = Some(xy);
+        }
+
+        Ok(())
+    }
+
+    fn push_ring<I, C>(&mut self, coords: I) -> Result<(), crate::Error>
+    where
+        I: Iterator<Item = C>,
+        C: CoordTrait,
+        C::T: ToPrimitive,
+    {
+        self.reserve(coords.size_hint().0 + 1, 1);
+
+        let mut coords = coords.map(coord_to_xy);
+        let Some(first) = coords.next().transpose()? else {
+            return Ok(());
+        };
+        let first_index = self.vertices.len();
+        self.push_point(first, true)?;
+
+        // Holds back each coordinate until the next one shows it isn't a repeat of the first.
+        let (mut previous, mut pending) = (first, None);
+        for xy in coords {
+            if let Some(pending) = pending.replace(xy?) {
+                self.push_resampled(previous, pending)?;
+                self.push_point(pending, false)?;
+                previous = pending;
+            }
+        }
+        match pending {
+            Some(last) if last != first => {
+                self.push_resampled(previous, last)?;
+                self.push_point(last, false)?;
+                previous = last;
+            }
+            _ if previous == first => return Ok(()),
+            _ => {}
+        }
+
+        self.push_resampled(previous, first)?;
+        let chunk_start = self.chunks.vertex_start();
+        match self.options.ring_closure {
+            RingClosure::Indexed
+                if first_index >= chunk_start
+                    && self.dasher.is_none()
+                    && !self.options.adds_line_distances() =>
+            {
+                // Both fit the index width, as they are in the current mesh.
+                let (first, last) = (
+                    (first_index - chunk_start) as u32,
+                    (self.vertices.len() - 1 - chunk_start) as u32,
+                );
+                match self.options.line_topology() {
+                    PrimitiveTopology::LineStrip => self.indices.push(first),
+                    PrimitiveTopology::PointList => {}
+                    _ => self.indices.extend([last, first]),
+                }
+                Ok(())
+            }
+            // Also when the ring was split across meshes and its first vertex is in another, or
+            // when the closing segment needs its own dashes or distances.
+            _ => self.push_point(first, false),
+        }
+    }
+
+    /// Runs `add` for a single line string or ring, then divides the distances it added by its
+    /// length.
+    fn with_progress(
+        &mut self,
+        add: impl FnOnce(&mut Self) -> Result<(), crate::Error>,
+    ) -> Result<(), crate::Error> {
+        if let Some(dash) = self.options.dash.as_ref().filter(|dash| !dash.is_valid()) {
+            return Err(crate::Error::InvalidDashPattern(dash.clone()));
+        }
+        let start = self.distances.len();
+        let result = add(self);
+        if self.options.adds_line_distances() {
+            let length = self.travelled as f32;
+            self.progress
+                .extend(
+                    self.distances[start..]
+                        .iter()
+                        .map(|distance| match length > 0. {
+                            true => distance / length,
+                            false => 0.,
+                        }),
+                );
+        }
+        result
+    }
+
+    /// Reserves room for `num_line_strings` more line strings with `num_coords` coordinates
+    /// between them. Resampling onto a height source adds vertices beyond these.
+    pub fn reserve(&mut self, num_coords: usize, num_line_strings: usize) {
+        self.vertices.reserve(num_coords);
+        if self.options.adds_line_distances() {
+            self.distances.reserve(num_coords);
+            self.progress.reserve(num_coords);
+        }
+        self.indices.reserve(match self.options.line_topology() {
+            PrimitiveTopology::LineStrip => num_coords + num_line_strings,
+            PrimitiveTopology::PointList => num_coords,
+            _ => 2 * num_coords.saturating_sub(num_line_strings),
+        });
+    }
+
+    /// Reserves room for the lines and line strings in `geometry`.
+    pub fn reserve_for(&mut self, geometry: &impl GeometryTrait) {
+        self.reserve_counts(&Counts::of(geometry));
+    }
+
+    pub(crate) fn reserve_counts(&mut self, counts: &Counts) {
+        self.reserve(counts.line_coords, counts.line_strings);
+    }
+
+    /// Removes all line strings, keeping the allocated buffers for reuse.
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.indices.clear();
+        self.distances.clear();
+        self.progress.clear();
+        self.styles.clear();
+        self.chunks.clear();
+    }
+
+    /// Number of vertices added so far.
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Number of vertices the builder can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.vertices.capacity()
+    }
+
+    pub(crate) fn num_indices(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Number of meshes the line strings are split into to stay within the index width.
+    pub fn num_meshes(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Builds one mesh per `num_meshes`.
+    pub fn build_meshes(&self) -> Result<Vec<Mesh>, crate::Error> {
+        self.chunks
+            .ranges(self.vertices.len(), self.indices.len())
+            .map(|(vertices, indices)| {
+                let (distances, progress) = match self.options.adds_line_distances() {
+                    true => (
+                        self.distances[vertices.clone()].to_vec(),
+                        self.progress[vertices.clone()].to_vec(),
+                    ),
+                    false => Default::default(),
+                };
+                line_mesh(
+                    self.vertices[vertices.clone()].to_vec(),
+                    self.indices[indices].to_vec(),
+                    distances,
+                    progress,
+                    self.styles.vertex_styles(vertices),
+                    &self.options,
+                )
+            })
+            .collect()
+    }
+
+    /// Appends the vertices that resampling onto the height source adds between `from` and `to`.
+    fn push_resampled(
+        &mut self,
+        [x0, y0]: [f64; 2],
+        [x1, y1]: [f64; 2],
+    ) -> Result<(), crate::Error> {
+        let Some(resolution) = self
+            .height_source
+            .as_ref()
+            .and_then(|height_source| height_source.resolution())
+            .filter(|resolution| *resolution > 0.0)
+        else {
+            return Ok(());
+        };
+        let steps = ((x1 - x0).hypot(y1 - y0) / resolution).ceil();
+        for step in 1..steps as usize {
+            let t = step as f64 / steps;
+            self.push_point([x0 + t * (x1 - x0), y0 + t * (y1 - y0)], false)?;
+        }
+        Ok(())
+    }
+
+    /// Appends a point of a line string, leaving out the gaps of the dash pattern.
+    fn push_point(&mut self, xy: [f64; 2], starts_line: bool) -> Result<(), crate::Error> {
+        let (from, travelled) = (self.pen, self.travelled);
+        let length = match starts_line {
+            true => 0.,
+            false => (xy[0] - from[0]).hypot(xy[1] - from[1]),
+        };
+        self.pen = xy;
+        self.travelled = travelled + length;
+        if starts_line {
+            self.travelled = 0.;
+        }
+
+        let Some(mut dasher) = self.dasher.take() else {
+            return self.push_vertex(xy, self.travelled, starts_line);
+        };
+        let result = self.push_dashed(&mut dasher, [from, xy], travelled, starts_line);
+        self.dasher = Some(dasher);
+        result
+    }
+
+    fn push_dashed(
+        &mut self,
+        dasher: &mut Dasher,
+        [from, to]: [[f64; 2]; 2],
+        travelled: f64,
+        starts_line: bool,
+    ) -> Result<(), crate::Error> {
+        if starts_line {
+            dasher.restart();
+            if dasher.is_on() {
+                self.push_vertex(to, 0., true)?;
             }
-            last_index = Some(index_base + i);
+            return Ok(());
         }
 
+        let length = (to[0] - from[0]).hypot(to[1] - from[1]);
+        let mut along = 0.;
+        while let Some(step) = dasher.next_switch(length - along) {
+            along += step;
+            let t = along / length;
+            let xy = [
+                from[0] + t * (to[0] - from[0]),
+                from[1] + t * (to[1] - from[1]),
+            ];
+            // Switching on starts a dash, switching off ends one.
+            self.push_vertex(xy, travelled + along, dasher.is_on())?;
+        }
+        if dasher.is_on() {
+            self.push_vertex(to, travelled + length, false)?;
+        }
+        Ok(())
+    }
+
+    /// Appends a vertex, connecting it to the previous one unless it starts a line string.
+    fn push_vertex(
+        &mut self,
+        [x, y]: [f64; 2],
+        distance: f64,
+        starts_line: bool,
+    ) -> Result<(), crate::Error> {
+        let z = self
+            .height_source
+            .as_ref()
+            .and_then(|height_source| height_source.height_at(x, y))
+            .unwrap_or(0.0);
+        let vertex = [
+            x.to_f32().ok_or(crate::Error::CouldNotConvertToF32)?,
+            y.to_f32().ok_or(crate::Error::CouldNotConvertToF32)?,
+            z + self.options.z_offset,
+        ];
+
+        if self.vertices.len() - self.chunks.vertex_start() >= self.options.max_line_vertices() {
+            let previous = self.vertices.last().copied();
+            let previous_distance = self.distances.last().copied().unwrap_or(0.);
+            self.chunks.start(self.vertices.len(), self.indices.len());
+            if let (false, Some(previous)) = (starts_line, previous) {
+                self.push_indexed(previous, previous_distance, true);
+            }
+        }
+        self.push_indexed(vertex, distance as f32, starts_line);
         Ok(())
     }
+
+    fn push_indexed(&mut self, vertex: Vertex, distance: f32, starts_line: bool) {
+        // Below `max_vertices`, so it fits the index width.
+        let index = (self.vertices.len() - self.chunks.vertex_start()) as u32;
+        self.vertices.push(vertex);
+        if self.options.adds_line_distances() {
+            self.distances.push(distance);
+        }
+
+        match self.options.line_topology() {
+            PrimitiveTopology::LineStrip => {
+                if starts_line && self.indices.len() > self.chunks.index_start() {
+                    self.indices.push(STRIP_RESTART);
+                }
+                self.indices.push(index);
+            }
+            PrimitiveTopology::PointList => self.indices.push(index),
+            _ if !starts_line => {
+                self.indices.push(index - 1);
+                self.indices.push(index);
+            }
+            _ => {}
+        }
+    }
 }
 
 impl TryFrom<LineStringMeshBuilder> for Mesh {
     type Error = crate::Error;
 
     fn try_from(line_string_mesh_builder: LineStringMeshBuilder) -> Result<Self, Self::Error> {
-        if line_string_mesh_builder.vertices.is_empty() {
-            Err(crate::Error::EmptyGeometry)
-        } else {
-            let vertices = line_string_mesh_builder.vertices;
-            let indices = line_string_mesh_builder.indices;
-            let num_vertices = vertices.len();
-            let mut mesh = Mesh::new(
-                bevy::render::render_resource::PrimitiveTopology::LineList,
-                Default::default(),
-            );
-            mesh.insert_indices(bevy::mesh::Indices::U32(indices));
-            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
+        if line_string_mesh_builder.num_meshes() > 1 {
+            return Err(crate::Error::IndexOverflow);
+        }
+        let styles = line_string_mesh_builder
+            .styles
+            .vertex_styles(0..line_string_mesh_builder.vertices.len());
+        line_mesh(
+            line_string_mesh_builder.vertices,
+            line_string_mesh_builder.indices,
+            line_string_mesh_builder.distances,
+            line_string_mesh_builder.progress,
+            styles,
+            &line_string_mesh_builder.options,
+        )
+    }
+}
 
-            let normals = vec![[0.0, 0.0, 0.0]; num_vertices];
-            let uvs = vec![[0.0, 0.0]; num_vertices];
+/// Builds a mesh from a copy of the buffers, leaving the builder ready for reuse.
+impl TryFrom<&LineStringMeshBuilder> for Mesh {
+    type Error = crate::Error;
 
-            mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
-            mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    fn try_from(line_string_mesh_builder: &LineStringMeshBuilder) -> Result<Self, Self::Error> {
+        if line_string_mesh_builder.num_meshes() > 1 {
+            return Err(crate::Error::IndexOverflow);
+        }
+        line_mesh(
+            line_string_mesh_builder.vertices.clone(),
+            line_string_mesh_builder.indices.clone(),
+            line_string_mesh_builder.distances.clone(),
+            line_string_mesh_builder.progress.clone(),
+            line_string_mesh_builder
+                .styles
+                .vertex_styles(0..line_string_mesh_builder.vertices.len()),
+            &line_string_mesh_builder.options,
+        )
+    }
+}
 
-            Ok(mesh)
+pub(crate) fn coord_to_xy<C: CoordTrait>(coord: C) -> Result<[f64; 2], crate::Error>
+where
+    C::T: ToPrimitive,
+{
+    Ok([
+        coord
+            .x()
+            .to_f64()
+            .ok_or(crate::Error::CouldNotConvertToF64)?,
+        coord
+            .y()
+            .to_f64()
+            .ok_or(crate::Error::CouldNotConvertToF64)?,
+    ])
+}
+
+fn line_mesh(
+    mut vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    distances: Vec<f32>,
+    progress: Vec<f32>,
+    styles: Option<Vec<FeatureStyle>>,
+    options: &MeshOptions,
+) -> Result<Mesh, crate::Error> {
+    if vertices.is_empty() {
+        return Err(crate::Error::EmptyGeometry);
+    }
+    if let Some(styles) = &styles {
+        apply_z_order(&mut vertices, styles);
+    }
+    if options.line_style == LineStyle::ScreenSpace {
+        return extruded_line_mesh(&vertices, &indices, distances, progress, styles, options);
+    }
+    if !matches!(
+        options.topology,
+        PrimitiveTopology::LineList | PrimitiveTopology::LineStrip | PrimitiveTopology::PointList
+    ) {
+        return Err(crate::Error::UnsupportedTopology(options.topology));
+    }
+
+    let num_vertices = vertices.len();
+    let mut mesh = Mesh::new(options.topology, options.asset_usages);
+    mesh.insert_indices(options.indices(indices, num_vertices)?);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
+
+    insert_line_attributes(
+        &mut mesh,
+        num_vertices,
+        distances,
+        progress,
+        styles,
+        options,
+    );
+    Ok(mesh)
+}
+
+pub(crate) fn apply_z_order(vertices: &mut [Vertex], styles: &[FeatureStyle]) {
+    for (vertex, style) in vertices.iter_mut().zip(styles) {
+        vertex[2] += style.z_order;
+    }
+}
+
+fn insert_line_attributes(
+    mesh: &mut Mesh,
+    num_vertices: usize,
+    distances: Vec<f32>,
+    progress: Vec<f32>,
+    styles: Option<Vec<FeatureStyle>>,
+    options: &MeshOptions,
+) {
+    if let Some(styles) = styles {
+        let colors = styles
+            .iter()
+            .map(|style| style.stroke_color.to_f32_array())
+            .collect::<Vec<_>>();
+        let widths = styles
+            .iter()
+            .map(|style| style.stroke_width)
+            .collect::<Vec<_>>();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        mesh.insert_attribute(ATTRIBUTE_LINE_WIDTH, widths);
+    }
+    if options.normals {
+        let normals = vec![[0.0, 0.0, 0.0]; num_vertices];
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    }
+    if options.uvs {
+        let uvs = vec![[0.0, 0.0]; num_vertices];
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    }
+    if options.adds_line_distances() {
+        mesh.insert_attribute(ATTRIBUTE_LINE_DISTANCE, distances);
+        mesh.insert_attribute(ATTRIBUTE_LINE_PROGRESS, progress);
+    }
+}
+
+/// Extruded vertices, as the line vertex each was made from and the direction it is pushed in,
+/// and the triangles between them.
+#[derive(Default)]
+struct Extrusion {
+    sources: Vec<u32>,
+    extrusions: Vec<[f32; 2]>,
+    triangles: Vec<u32>,
+}
+
+impl Extrusion {
+    /// Adds a quad for the segment from `a` to `b`. Returns the extruded vertices at either end.
+    fn push_segment(&mut self, vertices: &[Vertex], a: u32, b: u32) -> [[u32; 2]; 2] {
+        let ([xa, ya, _], [xb, yb, _]) = (vertices[a as usize], vertices[b as usize]);
+        let length = (xb - xa).hypot(yb - ya);
+        let normal = match length > 0. {
+            true => [(ya - yb) / length, (xb - xa) / length],
+            false => [0., 0.],
+        };
+
+        let first = self.sources.len() as u32;
+        for vertex in [a, b] {
+            self.sources.extend([vertex, vertex]);
+            self.extrusions.extend([normal, [-normal[0], -normal[1]]]);
+        }
+        self.triangles
+            .extend([first, first + 1, first + 3, first, first + 3, first + 2]);
+        [[first, first + 1], [first + 2, first + 3]]
+    }
+
+    /// Fills the gap at `vertex` between segments ending at `ends` and starting at `starts`.
+    fn push_join(&mut self, vertex: u32, [left0, right0]: [u32; 2], [left1, right1]: [u32; 2]) {
+        let center = self.sources.len() as u32;
+        self.sources.push(vertex);
+        self.extrusions.push([0., 0.]);
+        self.triangles
+            .extend([center, left0, left1, center, right0, right1]);
+    }
+}
+
+/// Turns each `LineList` segment into a quad whose vertices stay on the line, extruded to
+/// either side by the segment's normal. Where a segment starts at the end of the previous one,
+/// or ends at the start of its line string's first one, a vertex that isn't extruded fills the
+/// gap between them with a bevel.
+fn extruded_line_mesh(
+    vertices: &[Vertex],
+    indices: &[u32],
+    distances: Vec<f32>,
+    progress: Vec<f32>,
+    styles: Option<Vec<FeatureStyle>>,
+    options: &MeshOptions,
+) -> Result<Mesh, crate::Error> {
+    let mut extrusion = Extrusion::default();
+    // Line vertex and extruded vertices at the start of the current line string, and at the
+    // end of the previous segment.
+    let mut line_start: Option<(u32, [u32; 2])> = None;
+    let mut previous: Option<(u32, [u32; 2])> = None;
+    for segment in indices.chunks_exact(2) {
+        let (a, b) = (segment[0], segment[1]);
+        let [starts, ends] = extrusion.push_segment(vertices, a, b);
+        match previous {
+            Some((end, previous_ends)) if end == a => extrusion.push_join(a, previous_ends, starts),
+            _ => line_start = Some((a, starts)),
+        }
+        if let Some((start, line_starts)) = line_start {
+            if start == b && line_starts != starts {
+                extrusion.push_join(b, ends, line_starts);
+            }
         }
+        previous = Some((b, ends));
     }
+
+    let Extrusion {
+        sources,
+        extrusions,
+        triangles,
+    } = extrusion;
+    let num_vertices = sources.len();
+    let (distances, progress) = match options.adds_line_distances() {
+        true => (
+            from_sources(&sources, &distances),
+            from_sources(&sources, &progress),
+        ),
+        false => Default::default(),
+    };
+    let styles = styles.map(|styles| from_sources(&sources, &styles));
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList, options.asset_usages);
+    mesh.insert_indices(options.indices(triangles, num_vertices)?);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, from_sources(&sources, vertices));
+    mesh.insert_attribute(ATTRIBUTE_LINE_EXTRUSION, extrusions);
+    insert_line_attributes(
+        &mut mesh,
+        num_vertices,
+        distances,
+        progress,
+        styles,
+        options,
+    );
+    Ok(mesh)
+}
+
+/// The value of the line vertex each extruded vertex was made from.
+fn from_sources<T: Copy>(sources: &[u32], values: &[T]) -> Vec<T> {
+    sources
+        .iter()
+        .map(|source| values[*source as usize])
+        .collect()
 }
 
 impl crate::build_mesh::BuildMesh for LineStringMeshBuilder {
     fn build(self) -> Result<crate::GeometryMesh, crate::Error> {
         Ok(crate::GeometryMesh::LineString(self.try_into()?))
     }
+
+    fn build_ref(&self) -> Result<crate::GeometryMesh, crate::Error> {
+        Ok(crate::GeometryMesh::LineString(self.try_into()?))
+    }
+
+    fn build_meshes(&self) -> Result<Vec<crate::GeometryMesh>, crate::Error> {
+        Ok(self
+            .build_meshes()?
+            .into_iter()
+            .map(crate::GeometryMesh::LineString)
+            .collect())
+    }
 }
@@ -0,0 +1,1147 @@
+use crate::dash::{DashPattern, DashUnits};
+use crate::options::{LineStyle, MeshOptions, PolygonMode};
+use crate::style::{Expression, Interpolate, Properties, Style, Value};
+use crate::{BuildBevyMeshesContext, BuildMesh, GeometryMesh};
+use bevy::color::{palettes::css, Hsla, LinearRgba, Srgba};
+use geo_traits::{GeometryTrait, GeometryType};
+use geo_types::{
+    Coord, Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon,
+    Point, Polygon,
+};
+use serde_json::{Map, Value as Json};
+use std::collections::BTreeMap;
+
+/// The fill, line and circle layers of a MapLibre style JSON document, in drawing order, and
+/// its GeoJSON sources. Other layer and source types are skipped, except that vector tiles
+/// aren't decoded, so `from_json` fails with `Error::UnsupportedSource` on a fill, line or
+/// circle layer drawing from a vector source.
+///
+/// Paint properties may be constants, legacy `stops` functions or expressions built from
+/// `get`, `has`, `zoom`, `geometry-type`, `literal`, `match`, `step`, `interpolate`, `case`,
+/// comparisons, `all`, `any`, `!`, `in` and arithmetic. Filters may also use the legacy
+/// syntax. Anything else fails with `Error::InvalidStyle`.
+#[derive(Clone, Debug, Default)]
+pub struct MapStyle {
+    pub sources: BTreeMap<String, Source>,
+    pub layers: Vec<StyleLayer>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    /// `data` is the URL or path of a GeoJSON file, or the GeoJSON itself.
+    GeoJson { data: Json },
+}
+
+impl Source {
+    /// The features of a GeoJSON source whose `data` is the GeoJSON itself. `None` for GeoJSON
+    /// at a URL, which apps load themselves, e.g. passing GeoJSON files to `parse_geojson`.
+    pub fn features(&self) -> Option<Result<Vec<GeoJsonFeature>, crate::Error>> {
+        match self {
+            Source::GeoJson {
+                data: data @ Json::Object(_),
+            } => Some(parse_geojson(data)),
+            _ => None,
+        }
+    }
+}
+
+/// A GeoJSON feature's geometry and properties, ready for `StyleLayer::build_meshes`.
+pub type GeoJsonFeature = (Geometry<f64>, Map<String, Json>);
+
+/// The features of a GeoJSON feature collection, feature or bare geometry, skipping features
+/// without a geometry. Altitudes are dropped.
+pub fn parse_geojson(geojson: &Json) -> Result<Vec<GeoJsonFeature>, crate::Error> {
+    match geojson["type"].as_str() {
+        Some("FeatureCollection") => {
+            let features = geojson_list(&geojson["features"], parse_feature)?;
+            Ok(features.into_iter().flatten().collect())
+        }
+        Some("Feature") => Ok(parse_feature(geojson)?.into_iter().collect()),
+        _ => Ok(vec![(parse_geometry(geojson)?, Map::new())]),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayerKind {
+    /// Draws polygons.
+    Fill,
+    /// Draws line strings and polygon outlines.
+    Line,
+    /// Draws points.
+    Circle,
+}
+
+/// A layer of a `MapStyle`.
+#[derive(Clone, Debug)]
+pub struct StyleLayer {
+    pub id: String,
+    pub kind: LayerKind,
+    pub source: String,
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    /// `false` when the layer's `visibility` is `none`.
+    pub visible: bool,
+    /// Position among the style's layers, including skipped ones.
+    pub index: usize,
+    /// Added to the z of the layer's features, so that later layers are drawn over earlier
+    /// ones: `index` times the z step set with `MapStyle::with_z_step`, in geo units.
+    pub z_order: f32,
+    filter: Option<Expr>,
+    paint: Paint,
+}
+
+impl MapStyle {
+    pub fn from_json(json: &str) -> Result<Self, crate::Error> {
+        let style: Json = serde_json::from_str(json).map_err(crate::Error::Json)?;
+        let mut sources = BTreeMap::new();
+        for (name, source) in style["sources"].as_object().into_iter().flatten() {
+            let source = match source["type"].as_str() {
+                Some("geojson") => Source::GeoJson {
+                    data: source["data"].clone(),
+                },
+                _ => continue,
+            };
+            sources.insert(name.clone(), source);
+        }
+        let mut layers = Vec::new();
+        for (index, json) in style["layers"].as_array().into_iter().flatten().enumerate() {
+            let Some(layer) = StyleLayer::parse(index, json)? else {
+                continue;
+            };
+            if !sources.contains_key(&layer.source) {
+                return Err(match style["sources"].get(&layer.source) {
+                    Some(_) => crate::Error::UnsupportedSource(layer.source),
+                    None => invalid(json),
+                });
+            }
+            layers.push(layer);
+        }
+        Ok(MapStyle { sources, layers }.with_z_step(Self::DEFAULT_Z_STEP))
+    }
+
+    /// Z step between consecutive layers unless set with `with_z_step`.
+    pub const DEFAULT_Z_STEP: f32 = 1.;
+
+    /// Sets how far apart in z consecutive layers are drawn, in geo units. It needs to be
+    /// small next to the map's extent yet large enough for the depth buffer to tell the layers
+    /// apart, e.g. a centimeter in a projected CRS. 0 leaves drawing order to the renderer.
+    pub fn with_z_step(mut self, z_step: f32) -> Self {
+        for layer in &mut self.layers {
+            layer.z_order = layer.index as f32 * z_step;
+        }
+        self
+    }
+
+    /// The layers drawing features of `source`, in drawing order.
+    pub fn layers_for<'a>(&'a self, source: &'a str) -> impl Iterator<Item = &'a StyleLayer> {
+        self.layers
+            .iter()
+            .filter(move |layer| layer.source == source)
+    }
+}
+
+impl StyleLayer {
+    fn parse(index: usize, layer: &Json) -> Result<Option<Self>, crate::Error> {
+        let kind = match layer["type"].as_str() {
+            Some("fill") => LayerKind::Fill,
+            Some("line") => LayerKind::Line,
+            Some("circle") => LayerKind::Circle,
+            _ => return Ok(None),
+        };
+        let (Some(id), Some(source)) = (layer["id"].as_str(), layer["source"].as_str()) else {
+            return Err(invalid(layer));
+        };
+        let filter = match &layer["filter"] {
+            Json::Null => None,
+            filter => Some(parse_expr(filter)?),
+        };
+        Ok(Some(StyleLayer {
+            id: id.into(),
+            kind,
+            source: source.into(),
+            min_zoom: layer["minzoom"].as_f64().unwrap_or(0.) as f32,
+            max_zoom: layer["maxzoom"].as_f64().unwrap_or(24.) as f32,
+            visible: layer["layout"]["visibility"].as_str() != Some("none"),
+            index,
+            z_order: 0.,
+            filter,
+            paint: Paint::parse(kind, &layer["paint"])?,
+        }))
+    }
+
+    /// Whether the layer draws a feature at `zoom`: it must be visible, within the layer's
+    /// zoom range, of a kind of geometry the layer draws and pass its filter. Geometry
+    /// collections are never drawn.
+    pub fn matches(
+        &self,
+        geometry: &impl GeometryTrait,
+        properties: &dyn Properties,
+        zoom: f32,
+    ) -> bool {
+        let Some(geometry) = GeometryKind::of(geometry) else {
+            return false;
+        };
+        let draws = match self.kind {
+            LayerKind::Fill => geometry == GeometryKind::Polygon,
+            LayerKind::Line => geometry != GeometryKind::Point,
+            LayerKind::Circle => geometry == GeometryKind::Point,
+        };
+        let context = Context {
+            properties,
+            zoom: f64::from(zoom),
+            geometry: Some(geometry),
+        };
+        self.visible
+            && (self.min_zoom..self.max_zoom).contains(&zoom)
+            && draws
+            && self
+                .filter
+                .as_ref()
+                .is_none_or(|filter| filter.eval(&context) == Value::Bool(true))
+    }
+
+    /// The layer's paint properties at `zoom`, with MapLibre's defaults where unset. Circle
+    /// radii become point sizes, i.e. diameters.
+    pub fn style(&self, zoom: f32) -> Style {
+        let paint = &self.paint;
+        let color = with_opacity(
+            Node::at_zoom(&paint.color, zoom, LinearRgba::BLACK),
+            Node::at_zoom(&paint.opacity, zoom, 1.),
+        );
+        let style = Style::new().with_z_order(self.z_order);
+        match self.kind {
+            LayerKind::Fill => style
+                .with_stroke_color(match &paint.outline_color {
+                    Some(_) => with_opacity(
+                        Node::at_zoom(&paint.outline_color, zoom, LinearRgba::BLACK),
+                        Node::at_zoom(&paint.opacity, zoom, 1.),
+                    ),
+                    None => color.clone(),
+                })
+                .with_fill_color(color),
+            LayerKind::Line => style
+                .with_stroke_color(color)
+                .with_stroke_width(Node::at_zoom(&paint.width, zoom, 1.)),
+            LayerKind::Circle => style
+                .with_fill_color(color)
+                .with_point_size(combine(
+                    Node::at_zoom(&paint.radius, zoom, 5.),
+                    Expression::Constant(2.),
+                    |radius, scale| radius * scale,
+                ))
+                .with_stroke_color(Node::at_zoom(&paint.stroke_color, zoom, LinearRgba::BLACK))
+                .with_stroke_width(Node::at_zoom(&paint.stroke_width, zoom, 0.)),
+        }
+    }
+
+    /// How the layer's features are meshed. Line layers mesh with `LineStyle::ScreenSpace`, as
+    /// their widths are in pixels, and with `MeshOptions::line_distances` when dashed, for
+    /// `line_material` to dash them.
+    pub fn mesh_options(&self) -> MeshOptions {
+        let mut options = MeshOptions::default();
+        match self.kind {
+            LayerKind::Fill => {
+                options.polygon_mode = match self.paint.outline_color {
+                    Some(_) => PolygonMode::FillAndOutline,
+                    None => PolygonMode::Fill,
+                };
+            }
+            LayerKind::Line => {
+                options.polygon_mode = PolygonMode::Outline;
+                options.line_style = LineStyle::ScreenSpace;
+                options.line_distances = self.paint.dash.is_some();
+            }
+            LayerKind::Circle => {}
+        }
+        options
+    }
+
+    /// Meshes the features the layer draws at `zoom` with its style and mesh options, skipping
+    /// the others. Returns no meshes when it draws none of them.
+    pub fn build_meshes<Scalar, G, P>(
+        &self,
+        features: impl IntoIterator<Item = (G, P)>,
+        zoom: f32,
+    ) -> Result<Vec<GeometryMesh>, crate::Error>
+    where
+        Scalar: geo_types::CoordFloat,
+        G: GeometryTrait<T = Scalar>,
+        P: Properties,
+    {
+        let style = self.style(zoom);
+        let mut ctx = BuildBevyMeshesContext::new().with_options(self.mesh_options());
+        for (geometry, properties) in features {
+            if self.matches(&geometry, &properties, zoom) {
+                ctx.add_feature(&geometry, &properties, &style)?;
+            }
+        }
+        if ctx.is_empty() {
+            return Ok(Vec::new());
+        }
+        ctx.build_meshes()
+    }
+
+    /// A material for the meshes of a line layer, drawing each feature with the color and
+    /// width its vertices were styled with and dashing them with `line-dasharray`. `None` for
+    /// other layers.
+    #[cfg(feature = "material")]
+    pub fn line_material(&self) -> Result<Option<crate::ScreenSpaceLineMaterial>, crate::Error> {
+        if self.kind != LayerKind::Line {
+            return Ok(None);
+        }
+        // At a width of 1, the dash pattern's pixels are line widths.
+        let material =
+            crate::ScreenSpaceLineMaterial::new(LinearRgba::WHITE, 1.).with_vertex_styles();
+        match &self.paint.dash {
+            Some(dash) => material.with_dash(dash).map(Some),
+            None => Ok(Some(material)),
+        }
+    }
+}
+
+impl Properties for Map<String, Json> {
+    fn property(&self, name: &str) -> Option<Value> {
+        self.get(name).and_then(json_value)
+    }
+}
+
+/// The paint properties of a layer that geo-bevy draws.
+#[derive(Clone, Debug, Default)]
+struct Paint {
+    color: Option<Node<LinearRgba>>,
+    opacity: Option<Node<f32>>,
+    outline_color: Option<Node<LinearRgba>>,
+    width: Option<Node<f32>>,
+    /// `line-dasharray`, in line widths.
+    dash: Option<DashPattern>,
+    radius: Option<Node<f32>>,
+    stroke_color: Option<Node<LinearRgba>>,
+    stroke_width: Option<Node<f32>>,
+}
+
+impl Paint {
+    fn parse(kind: LayerKind, paint: &Json) -> Result<Self, crate::Error> {
+        Ok(match kind {
+            LayerKind::Fill => Paint {
+                color: parse_property(&paint["fill-color"])?,
+                opacity: parse_property(&paint["fill-opacity"])?,
+                outline_color: parse_property(&paint["fill-outline-color"])?,
+                ..Default::default()
+            },
+            LayerKind::Line => Paint {
+                color: parse_property(&paint["line-color"])?,
+                opacity: parse_property(&paint["line-opacity"])?,
+                width: parse_property(&paint["line-width"])?,
+                dash: parse_dash(&paint["line-dasharray"])?,
+                ..Default::default()
+            },
+            LayerKind::Circle => Paint {
+                color: parse_property(&paint["circle-color"])?,
+                opacity: parse_property(&paint["circle-opacity"])?,
+                radius: parse_property(&paint["circle-radius"])?,
+                stroke_color: parse_property(&paint["circle-stroke-color"])?,
+                stroke_width: parse_property(&paint["circle-stroke-width"])?,
+                ..Default::default()
+            },
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GeometryKind {
+    Point,
+    LineString,
+    Polygon,
+}
+
+impl GeometryKind {
+    fn of(geometry: &impl GeometryTrait) -> Option<Self> {
+        match geometry.as_type() {
+            GeometryType::Point(_) | GeometryType::MultiPoint(_) => Some(GeometryKind::Point),
+            GeometryType::Line(_)
+            | GeometryType::LineString(_)
+            | GeometryType::MultiLineString(_) => Some(GeometryKind::LineString),
+            GeometryType::Polygon(_)
+            | GeometryType::MultiPolygon(_)
+            | GeometryType::Rect(_)
+            | GeometryType::Triangle(_) => Some(GeometryKind::Polygon),
+            GeometryType::GeometryCollection(_) => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            GeometryKind::Point => "Point",
+            GeometryKind::LineString => "LineString",
+            GeometryKind::Polygon => "Polygon",
+        }
+    }
+}
+
+/// What expressions are evaluated against.
+struct Context<'a> {
+    properties: &'a dyn Properties,
+    zoom: f64,
+    geometry: Option<GeometryKind>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Arithmetic {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// An expression evaluating to a property-like value, as filters and the inputs of paint
+/// expressions do.
+#[derive(Clone, Debug)]
+enum Expr {
+    Literal(Value),
+    Get(String),
+    Has(String),
+    Zoom,
+    GeometryType,
+    Not(Box<Expr>),
+    All(Vec<Expr>),
+    Any(Vec<Expr>),
+    Compare(Comparison, Box<Expr>, Box<Expr>),
+    In(Box<Expr>, Vec<Value>),
+    Math(Arithmetic, Vec<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, context: &Context) -> Value {
+        match self {
+            Expr::Literal(value) => value.clone(),
+            Expr::Get(name) => context.properties.property(name).unwrap_or(Value::Null),
+            Expr::Has(name) => Value::Bool(context.properties.property(name).is_some()),
+            Expr::Zoom => Value::Number(context.zoom),
+            Expr::GeometryType => context
+                .geometry
+                .map_or(Value::Null, |geometry| geometry.name().into()),
+            Expr::Not(expr) => Value::Bool(expr.eval(context) != Value::Bool(true)),
+            Expr::All(exprs) => Value::Bool(
+                exprs
+                    .iter()
+                    .all(|expr| expr.eval(context) == Value::Bool(true)),
+            ),
+            Expr::Any(exprs) => Value::Bool(
+                exprs
+                    .iter()
+                    .any(|expr| expr.eval(context) == Value::Bool(true)),
+            ),
+            Expr::Compare(comparison, left, right) => {
+                let (left, right) = (left.eval(context), right.eval(context));
+                let ordering = match (&left, &right) {
+                    (Value::Number(left), Value::Number(right)) => left.partial_cmp(right),
+                    (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+                    _ => None,
+                };
+                Value::Bool(match comparison {
+                    Comparison::Equal => left == right,
+                    Comparison::NotEqual => left != right,
+                    Comparison::Less => ordering.is_some_and(|ordering| ordering.is_lt()),
+                    Comparison::LessOrEqual => ordering.is_some_and(|ordering| ordering.is_le()),
+                    Comparison::Greater => ordering.is_some_and(|ordering| ordering.is_gt()),
+                    Comparison::GreaterOrEqual => ordering.is_some_and(|ordering| ordering.is_ge()),
+                })
+            }
+            Expr::In(needle, haystack) => Value::Bool(haystack.contains(&needle.eval(context))),
+            Expr::Math(arithmetic, exprs) => {
+                let Some(numbers) = exprs
+                    .iter()
+                    .map(|expr| expr.eval(context).as_number())
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return Value::Null;
+                };
+                Value::Number(match (arithmetic, numbers.as_slice()) {
+                    (Arithmetic::Add, numbers) => numbers.iter().sum(),
+                    (Arithmetic::Multiply, numbers) => numbers.iter().product(),
+                    (Arithmetic::Subtract, [number]) => -number,
+                    (Arithmetic::Subtract, [left, right]) => left - right,
+                    (Arithmetic::Divide, [left, right]) => left / right,
+                    _ => return Value::Null,
+                })
+            }
+        }
+    }
+
+    fn depends_on_data(&self) -> bool {
+        match self {
+            Expr::Literal(_) | Expr::Zoom => false,
+            Expr::Get(_) | Expr::Has(_) | Expr::GeometryType => true,
+            Expr::Not(expr) => expr.depends_on_data(),
+            Expr::All(exprs) | Expr::Any(exprs) | Expr::Math(_, exprs) => {
+                exprs.iter().any(Expr::depends_on_data)
+            }
+            Expr::Compare(_, left, right) => left.depends_on_data() || right.depends_on_data(),
+            Expr::In(needle, _) => needle.depends_on_data(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Curve {
+    Linear,
+    Exponential(f64),
+}
+
+impl Curve {
+    /// How far `input` is from `from` to `to`.
+    fn progress(self, input: f64, from: f64, to: f64) -> f32 {
+        let progress = match self {
+            Curve::Exponential(base) if base != 1. => {
+                (base.powf(input - from) - 1.) / (base.powf(to - from) - 1.)
+            }
+            _ => (input - from) / (to - from),
+        };
+        progress as f32
+    }
+}
+
+/// A paint value, or the expression computing it.
+#[derive(Clone, Debug)]
+enum Node<T> {
+    Constant(T),
+    Input(Expr),
+    Match {
+        input: Expr,
+        cases: Vec<(Vec<Value>, Node<T>)>,
+        default: Option<Box<Node<T>>>,
+    },
+    Step {
+        input: Expr,
+        base: Box<Node<T>>,
+        steps: Vec<(f64, Node<T>)>,
+    },
+    Interpolate {
+        curve: Curve,
+        input: Expr,
+        stops: Vec<(f64, Node<T>)>,
+    },
+    Case {
+        branches: Vec<(Expr, Node<T>)>,
+        default: Box<Node<T>>,
+    },
+}
+
+impl<T: PaintValue> Node<T> {
+    fn eval(&self, context: &Context) -> Option<T> {
+        match self {
+            Node::Constant(value) => Some(value.clone()),
+            Node::Input(expr) => T::from_value(&expr.eval(context)),
+            Node::Match {
+                input,
+                cases,
+                default,
+            } => {
+                let input = input.eval(context);
+                match cases.iter().find(|(labels, _)| labels.contains(&input)) {
+                    Some((_, output)) => output.eval(context),
+                    None => default.as_ref()?.eval(context),
+                }
+            }
+            Node::Step { input, base, steps } => {
+                let input = input.eval(context).as_number()?;
+                match steps.partition_point(|(threshold, _)| *threshold <= input) {
+                    0 => base.eval(context),
+                    reached => steps[reached - 1].1.eval(context),
+                }
+            }
+            Node::Interpolate {
+                curve,
+                input,
+                stops,
+            } => {
+                let input = input.eval(context).as_number()?;
+                let after = stops.partition_point(|(stop, _)| *stop <= input);
+                match (stops.get(after.wrapping_sub(1)), stops.get(after)) {
+                    (Some((from, low)), Some((to, high))) => Some(
+                        low.eval(context)?
+                            .interpolate(&high.eval(context)?, curve.progress(input, *from, *to)),
+                    ),
+                    (Some((_, output)), None) | (None, Some((_, output))) => output.eval(context),
+                    (None, None) => None,
+                }
+            }
+            Node::Case { branches, default } => {
+                match branches
+                    .iter()
+                    .find(|(condition, _)| condition.eval(context) == Value::Bool(true))
+                {
+                    Some((_, output)) => output.eval(context),
+                    None => default.eval(context),
+                }
+            }
+        }
+    }
+
+    fn depends_on_data(&self) -> bool {
+        match self {
+            Node::Constant(_) => false,
+            Node::Input(expr) => expr.depends_on_data(),
+            Node::Match {
+                input,
+                cases,
+                default,
+            } => {
+                input.depends_on_data()
+                    || cases.iter().any(|(_, node)| node.depends_on_data())
+                    || default.as_ref().is_some_and(|node| node.depends_on_data())
+            }
+            Node::Step { input, base, steps } => {
+                input.depends_on_data()
+                    || base.depends_on_data()
+                    || steps.iter().any(|(_, node)| node.depends_on_data())
+            }
+            Node::Interpolate { input, stops, .. } => {
+                input.depends_on_data() || stops.iter().any(|(_, node)| node.depends_on_data())
+            }
+            Node::Case { branches, default } => {
+                branches
+                    .iter()
+                    .any(|(condition, node)| condition.depends_on_data() || node.depends_on_data())
+                    || default.depends_on_data()
+            }
+        }
+    }
+
+    /// `node` at `zoom` as an `Expression` of feature properties, falling back to `default`.
+    fn at_zoom(node: &Option<Self>, zoom: f32, default: T) -> Expression<T> {
+        let zoom = f64::from(zoom);
+        match node {
+            None => Expression::Constant(default),
+            Some(node) if !node.depends_on_data() => Expression::Constant(
+                node.eval(&Context {
+                    properties: &BTreeMap::<String, Value>::new(),
+                    zoom,
+                    geometry: None,
+                })
+                .unwrap_or(default),
+            ),
+            Some(node) => {
+                let node = node.clone();
+                Expression::function(move |properties| {
+                    Some(
+                        node.eval(&Context {
+                            properties,
+                            zoom,
+                            geometry: None,
+                        })
+                        .unwrap_or_else(|| default.clone()),
+                    )
+                })
+            }
+        }
+    }
+}
+
+/// The types of paint properties.
+trait PaintValue: Interpolate + Send + Sync + 'static {
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+impl PaintValue for f32 {
+    fn from_value(value: &Value) -> Option<Self> {
+        value.as_number().map(|number| number as f32)
+    }
+}
+
+impl PaintValue for LinearRgba {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(color) => parse_color(color),
+            _ => None,
+        }
+    }
+}
+
+fn invalid(json: &Json) -> crate::Error {
+    crate::Error::InvalidStyle(json.to_string())
+}
+
+fn json_value(json: &Json) -> Option<Value> {
+    match json {
+        Json::Null => Some(Value::Null),
+        Json::Bool(value) => Some(Value::Bool(*value)),
+        Json::Number(number) => number.as_f64().map(Value::Number),
+        Json::String(string) => Some(Value::String(string.clone())),
+        Json::Array(_) | Json::Object(_) => None,
+    }
+}
+
+fn literal(json: &Json) -> Result<Value, crate::Error> {
+    json_value(json).ok_or_else(|| invalid(json))
+}
+
+fn number(json: &Json) -> Result<f64, crate::Error> {
+    json.as_f64().ok_or_else(|| invalid(json))
+}
+
+fn parse_property<T: PaintValue>(json: &Json) -> Result<Option<Node<T>>, crate::Error> {
+    match json {
+        Json::Null => Ok(None),
+        json => parse_node(json).map(Some),
+    }
+}
+
+fn parse_dash(json: &Json) -> Result<Option<DashPattern>, crate::Error> {
+    let lengths = match json {
+        Json::Null => return Ok(None),
+        Json::Array(array) if array.first() == Some(&Json::from("literal")) => {
+            return parse_dash(array.get(1).ok_or_else(|| invalid(json))?);
+        }
+        Json::Array(array) => array.iter().map(number).collect::<Result<Vec<_>, _>>()?,
+        json => return Err(invalid(json)),
+    };
+    let dash = DashPattern::new(lengths, DashUnits::Screen);
+    match dash.is_valid() {
+        true => Ok(Some(dash)),
+        false => Err(invalid(json)),
+    }
+}
+
+fn parse_node<T: PaintValue>(json: &Json) -> Result<Node<T>, crate::Error> {
+    let array = match json {
+        Json::Object(function) => return parse_function(json, function),
+        Json::Array(array) => array,
+        json => {
+            return json_value(json)
+                .and_then(|value| T::from_value(&value))
+                .map(Node::Constant)
+                .ok_or_else(|| invalid(json))
+        }
+    };
+    let arguments = array.get(1..).unwrap_or_default();
+    Ok(match array.first().and_then(Json::as_str) {
+        Some("literal") => parse_node(arguments.first().ok_or_else(|| invalid(json))?)?,
+        Some("match") => {
+            let [input, cases @ .., default] = arguments else {
+                return Err(invalid(json));
+            };
+            if !cases.len().is_multiple_of(2) {
+                return Err(invalid(json));
+            }
+            Node::Match {
+                input: parse_expr(input)?,
+                cases: cases
+                    .chunks(2)
+                    .map(|case| {
+                        let labels = match &case[0] {
+                            Json::Array(labels) => labels.iter().map(literal).collect(),
+                            label => literal(label).map(|label| vec![label]),
+                        };
+                        Ok((labels?, parse_node(&case[1])?))
+                    })
+                    .collect::<Result<_, crate::Error>>()?,
+                default: Some(Box::new(parse_node(default)?)),
+            }
+        }
+        Some("step") => {
+            let [input, base, steps @ ..] = arguments else {
+                return Err(invalid(json));
+            };
+            Node::Step {
+                input: parse_expr(input)?,
+                base: Box::new(parse_node(base)?),
+                steps: parse_stops(json, steps)?,
+            }
+        }
+        Some("interpolate" | "interpolate-hcl" | "interpolate-lab") => {
+            let [curve, input, stops @ ..] = arguments else {
+                return Err(invalid(json));
+            };
+            let curve = match curve.as_array().map(Vec::as_slice) {
+                Some([name]) if name == "linear" => Curve::Linear,
+                Some([name, base]) if name == "exponential" => Curve::Exponential(number(base)?),
+                _ => return Err(invalid(curve)),
+            };
+            Node::Interpolate {
+                curve,
+                input: parse_expr(input)?,
+                stops: parse_stops(json, stops)?,
+            }
+        }
+        Some("case") => {
+            let [branches @ .., default] = arguments else {
+                return Err(invalid(json));
+            };
+            if !branches.len().is_multiple_of(2) {
+                return Err(invalid(json));
+            }
+            Node::Case {
+                branches: branches
+                    .chunks(2)
+                    .map(|branch| Ok((parse_expr(&branch[0])?, parse_node(&branch[1])?)))
+                    .collect::<Result<_, crate::Error>>()?,
+                default: Box::new(parse_node(default)?),
+            }
+        }
+        _ => Node::Input(parse_expr(json)?),
+    })
+}
+
+/// Parses the `input, output` pairs of `step` and `interpolate` expressions.
+fn parse_stops<T: PaintValue>(
+    json: &Json,
+    stops: &[Json],
+) -> Result<Vec<(f64, Node<T>)>, crate::Error> {
+    if !stops.len().is_multiple_of(2) {
+        return Err(invalid(json));
+    }
+    stops
+        .chunks(2)
+        .map(|stop| Ok((number(&stop[0])?, parse_node(&stop[1])?)))
+        .collect()
+}
+
+/// Parses a legacy `{"stops": …}` function, of the zoom or of a `property`.
+fn parse_function<T: PaintValue>(
+    json: &Json,
+    function: &Map<String, Json>,
+) -> Result<Node<T>, crate::Error> {
+    let input = match function.get("property") {
+        Some(Json::String(property)) => Expr::Get(property.clone()),
+        None => Expr::Zoom,
+        Some(_) => return Err(invalid(json)),
+    };
+    let stops = || {
+        function
+            .get("stops")
+            .and_then(Json::as_array)
+            .ok_or_else(|| invalid(json))?
+            .iter()
+            .map(|stop| match stop.as_array().map(Vec::as_slice) {
+                Some([input, output]) => Ok((input, parse_node(output)?)),
+                _ => Err(invalid(stop)),
+            })
+            .collect::<Result<Vec<_>, crate::Error>>()
+    };
+    let numeric = |stops: Vec<(&Json, Node<T>)>| {
+        stops
+            .into_iter()
+            .map(|(input, output)| Ok((number(input)?, output)))
+            .collect::<Result<Vec<_>, crate::Error>>()
+    };
+    Ok(match function.get("type").and_then(Json::as_str) {
+        Some("identity") => Node::Input(input),
+        Some("categorical") => Node::Match {
+            input,
+            cases: stops()?
+                .into_iter()
+                .map(|(input, output)| Ok((vec![literal(input)?], output)))
+                .collect::<Result<_, crate::Error>>()?,
+            default: function
+                .get("default")
+                .map(parse_node)
+                .transpose()?
+                .map(Box::new),
+        },
+        Some("interval") => {
+            let steps = numeric(stops()?)?;
+            Node::Step {
+                input,
+                base: Box::new(steps.first().ok_or_else(|| invalid(json))?.1.clone()),
+                steps,
+            }
+        }
+        None | Some("exponential") => Node::Interpolate {
+            curve: Curve::Exponential(function.get("base").map_or(Ok(1.), number)?),
+            input,
+            stops: numeric(stops()?)?,
+        },
+        Some(_) => return Err(invalid(json)),
+    })
+}
+
+fn parse_expr(json: &Json) -> Result<Expr, crate::Error> {
+    let array = match json {
+        Json::Array(array) => array,
+        json => return literal(json).map(Expr::Literal),
+    };
+    let arguments = array.get(1..).unwrap_or_default();
+    let parse_all = || arguments.iter().map(parse_expr).collect::<Result<_, _>>();
+    // Legacy filters name properties with bare strings where expressions have `["get", …]`.
+    let legacy = arguments.first().is_some_and(Json::is_string);
+    let key = |name: &Json| match name.as_str() {
+        Some("$type") => Ok(Expr::GeometryType),
+        Some(name) => Ok(Expr::Get(name.into())),
+        None => Err(invalid(json)),
+    };
+    let comparison = match array.first().and_then(Json::as_str) {
+        Some("==") => Comparison::Equal,
+        Some("!=") => Comparison::NotEqual,
+        Some("<") => Comparison::Less,
+        Some("<=") => Comparison::LessOrEqual,
+        Some(">") => Comparison::Greater,
+        Some(">=") => Comparison::GreaterOrEqual,
+        Some(operator) => {
+            let arithmetic = match operator {
+                "+" => Arithmetic::Add,
+                "-" => Arithmetic::Subtract,
+                "*" => Arithmetic::Multiply,
+                "/" => Arithmetic::Divide,
+                _ => return parse_operator(json, operator, arguments, legacy),
+            };
+            return parse_all().map(|exprs| Expr::Math(arithmetic, exprs));
+        }
+        None => return Err(invalid(json)),
+    };
+    let [left, right] = arguments else {
+        return Err(invalid(json));
+    };
+    let left = match legacy && !right.is_array() {
+        true => key(left)?,
+        false => parse_expr(left)?,
+    };
+    Ok(Expr::Compare(
+        comparison,
+        Box::new(left),
+        Box::new(parse_expr(right)?),
+    ))
+}
+
+/// Parses the expressions that aren't comparisons or arithmetic.
+fn parse_operator(
+    json: &Json,
+    operator: &str,
+    arguments: &[Json],
+    legacy: bool,
+) -> Result<Expr, crate::Error> {
+    let parse_all = || arguments.iter().map(parse_expr).collect::<Result<_, _>>();
+    let name = || {
+        arguments
+            .first()
+            .and_then(Json::as_str)
+            .map(String::from)
+            .ok_or_else(|| invalid(json))
+    };
+    let legacy_in = || {
+        let (needle, haystack) = arguments.split_first().ok_or_else(|| invalid(json))?;
+        Ok::<_, crate::Error>(Expr::In(
+            Box::new(match needle.as_str() {
+                Some("$type") => Expr::GeometryType,
+                _ => Expr::Get(name()?),
+            }),
+            haystack.iter().map(literal).collect::<Result<_, _>>()?,
+        ))
+    };
+    Ok(match operator {
+        "literal" => Expr::Literal(literal(arguments.first().ok_or_else(|| invalid(json))?)?),
+        "get" => Expr::Get(name()?),
+        "has" => Expr::Has(name()?),
+        "!has" => Expr::Not(Box::new(Expr::Has(name()?))),
+        "zoom" => Expr::Zoom,
+        "geometry-type" => Expr::GeometryType,
+        "!" => match arguments {
+            [expr] => Expr::Not(Box::new(parse_expr(expr)?)),
+            _ => return Err(invalid(json)),
+        },
+        "all" => Expr::All(parse_all()?),
+        "any" => Expr::Any(parse_all()?),
+        "none" => Expr::Not(Box::new(Expr::Any(parse_all()?))),
+        "in" if legacy && !arguments.get(1).is_some_and(Json::is_array) => legacy_in()?,
+        "!in" => Expr::Not(Box::new(legacy_in()?)),
+        "in" => {
+            let [needle, haystack] = arguments else {
+                return Err(invalid(json));
+            };
+            let haystack = match haystack.as_array().map(Vec::as_slice) {
+                Some([name, Json::Array(values)]) if name == "literal" => values,
+                _ => return Err(invalid(haystack)),
+            };
+            Expr::In(
+                Box::new(parse_expr(needle)?),
+                haystack.iter().map(literal).collect::<Result<_, _>>()?,
+            )
+        }
+        _ => return Err(invalid(json)),
+    })
+}
+
+/// Parses a CSS color: hex, `rgb()`, `rgba()`, `hsl()`, `hsla()` or one of the basic names.
+fn parse_color(color: &str) -> Option<LinearRgba> {
+    let color = color.trim();
+    if color.starts_with('#') {
+        return Srgba::hex(color).ok().map(Into::into);
+    }
+    let Some((function, arguments)) = color
+        .strip_suffix(')')
+        .and_then(|color| color.split_once('('))
+    else {
+        return named_color(color).map(Into::into);
+    };
+    let arguments = arguments.split(',').map(str::trim).collect::<Vec<_>>();
+    let alpha = match arguments.get(3) {
+        Some(alpha) => alpha.parse().ok()?,
+        None => 1.,
+    };
+    let channel = |channel: &str| match channel.strip_suffix('%') {
+        Some(percent) => percent
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .map(|percent| percent / 100.),
+        None => channel.parse::<f32>().ok().map(|channel| channel / 255.),
+    };
+    let percent = |percent: &str| {
+        percent
+            .strip_suffix('%')?
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .map(|percent| percent / 100.)
+    };
+    match (function.trim(), arguments.as_slice()) {
+        ("rgb", [red, green, blue]) | ("rgba", [red, green, blue, _]) => {
+            Some(Srgba::new(channel(red)?, channel(green)?, channel(blue)?, alpha).into())
+        }
+        ("hsl", [hue, saturation, lightness]) | ("hsla", [hue, saturation, lightness, _]) => Some(
+            Hsla::new(
+                hue.parse().ok()?,
+                percent(saturation)?,
+                percent(lightness)?,
+                alpha,
+            )
+            .into(),
+        ),
+        _ => None,
+    }
+}
+
+fn named_color(name: &str) -> Option<Srgba> {
+    Some(match name.to_ascii_lowercase().as_str() {
+        "transparent" => Srgba::NONE,
+        "black" => css::BLACK,
+        "white" => css::WHITE,
+        "gray" | "grey" => css::GRAY,
+        "silver" => css::SILVER,
+        "red" => css::RED,
+        "maroon" => css::MAROON,
+        "orange" => css::ORANGE,
+        "yellow" => css::YELLOW,
+        "olive" => css::OLIVE,
+        "lime" => css::LIME,
+        "green" => css::GREEN,
+        "teal" => css::TEAL,
+        "aqua" | "cyan" => css::AQUA,
+        "blue" => css::BLUE,
+        "navy" => css::NAVY,
+        "fuchsia" | "magenta" => css::FUCHSIA,
+        "purple" => css::PURPLE,
+        _ => return None,
+    })
+}
+
+/// `color` with its alpha multiplied by `opacity`.
+fn with_opacity(color: Expression<LinearRgba>, opacity: Expression<f32>) -> Expression<LinearRgba> {
+    combine(color, opacity, |color, opacity| LinearRgba {
+        alpha: color.alpha * opacity,
+        ..color
+    })
+}
+
+fn combine<A, B, T>(
+    a: Expression<A>,
+    b: Expression<B>,
+    combine: impl Fn(A, B) -> T + Send + Sync + 'static,
+) -> Expression<T>
+where
+    A: Interpolate + Send + Sync + 'static,
+    B: Interpolate + Send + Sync + 'static,
+{
+    match (a, b) {
+        (Expression::Constant(a), Expression::Constant(b)) => Expression::Constant(combine(a, b)),
+        (a, b) => Expression::function(move |properties| {
+            Some(combine(a.evaluate(properties)?, b.evaluate(properties)?))
+        }),
+    }
+}
+
+fn invalid_geojson(json: &Json) -> crate::Error {
+    crate::Error::InvalidGeoJson(json.to_string())
+}
+
+fn geojson_list<T>(
+    json: &Json,
+    parse: impl Fn(&Json) -> Result<T, crate::Error>,
+) -> Result<Vec<T>, crate::Error> {
+    json.as_array()
+        .ok_or_else(|| invalid_geojson(json))?
+        .iter()
+        .map(parse)
+        .collect()
+}
+
+fn parse_feature(feature: &Json) -> Result<Option<GeoJsonFeature>, crate::Error> {
+    if feature["type"] != "Feature" {
+        return Err(invalid_geojson(feature));
+    }
+    let properties = match &feature["properties"] {
+        Json::Object(properties) => properties.clone(),
+        Json::Null => Map::new(),
+        _ => return Err(invalid_geojson(feature)),
+    };
+    match &feature["geometry"] {
+        Json::Null => Ok(None),
+        geometry => Ok(Some((parse_geometry(geometry)?, properties))),
+    }
+}
+
+fn parse_geometry(geometry: &Json) -> Result<Geometry<f64>, crate::Error> {
+    let coordinates = &geometry["coordinates"];
+    Ok(match geometry["type"].as_str() {
+        Some("Point") => Geometry::Point(Point(parse_coord(coordinates)?)),
+        Some("MultiPoint") => {
+            Geometry::MultiPoint(MultiPoint(geojson_list(coordinates, |coord| {
+                parse_coord(coord).map(Point)
+            })?))
+        }
+        Some("LineString") => Geometry::LineString(parse_line_string(coordinates)?),
+        Some("MultiLineString") => Geometry::MultiLineString(MultiLineString(geojson_list(
+            coordinates,
+            parse_line_string,
+        )?)),
+        Some("Polygon") => Geometry::Polygon(parse_polygon(coordinates)?),
+        Some("MultiPolygon") => {
+            Geometry::MultiPolygon(MultiPolygon(geojson_list(coordinates, parse_polygon)?))
+        }
+        Some("GeometryCollection") => Geometry::GeometryCollection(GeometryCollection(
+            geojson_list(&geometry["geometries"], parse_geometry)?,
+        )),
+        _ => return Err(invalid_geojson(geometry)),
+    })
+}
+
+fn parse_coord(json: &Json) -> Result<Coord<f64>, crate::Error> {
+    match json.as_array().map(Vec::as_slice) {
+        Some([x, y, ..]) => Ok(Coord {
+            x: x.as_f64().ok_or_else(|| invalid_geojson(json))?,
+            y: y.as_f64().ok_or_else(|| invalid_geojson(json))?,
+        }),
+        _ => Err(invalid_geojson(json)),
+    }
+}
+
+fn parse_line_string(json: &Json) -> Result<LineString<f64>, crate::Error> {
+    geojson_list(json, parse_coord).map(LineString)
+}
+
+/// The first ring is the exterior, the others holes.
+fn parse_polygon(json: &Json) -> Result<Polygon<f64>, crate::Error> {
+    let mut rings = geojson_list(json, parse_line_string)?.into_iter();
+    let exterior = rings.next().unwrap_or_else(|| LineString(Vec::new()));
+    Ok(Polygon::new(exterior, rings.collect()))
+}
@@ -0,0 +1,285 @@
+use crate::chunks::Chunks;
+use crate::height::HeightSource;
+use crate::line_string::coord_to_xy;
+use crate::options::MeshOptions;
+use bevy::prelude::Mesh;
+use geo_traits::{CoordTrait, LineStringTrait, LineTrait};
+use num_traits::ToPrimitive;
+use std::iter;
+use std::sync::Arc;
+
+type Vertex = [f32; 3]; // [x, y, z]
+
+/// Glyph placed along line strings by `MarkerMeshBuilder`, pointing in their direction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MarkerShape {
+    /// A triangular head on a shaft.
+    #[default]
+    Arrow,
+    /// A `>` shape.
+    Chevron,
+}
+
+impl MarkerShape {
+    /// Vertices within a unit square centred on the marker, pointing along +x, and
+    /// counter-clockwise triangles between them.
+    fn glyph(self) -> (&'static [[f64; 2]], &'static [u32]) {
+        match self {
+            MarkerShape::Arrow => (
+                &[
+                    [-0.5, -0.125],
+                    [0., -0.125],
+                    [0., 0.125],
+                    [-0.5, 0.125],
+                    [0., -0.5],
+                    [0.5, 0.],
+                    [0., 0.5],
+                ],
+                &[0, 1, 2, 0, 2, 3, 4, 5, 6],
+            ),
+            MarkerShape::Chevron => (
+                &[
+                    [0.5, 0.],
+                    [-0.25, 0.5],
+                    [-0.5, 0.5],
+                    [0.25, 0.],
+                    [-0.5, -0.5],
+                    [-0.25, -0.5],
+                ],
+                &[0, 1, 2, 0, 2, 3, 0, 3, 4, 0, 4, 5],
+            ),
+        }
+    }
+}
+
+/// Where `MarkerMeshBuilder` places markers, in geo units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Markers {
+    pub shape: MarkerShape,
+    /// Distance between markers along a line string.
+    pub spacing: f64,
+    /// Width and length of a marker.
+    pub size: f64,
+    /// Distance from the start of each line string to its first marker.
+    pub offset: f64,
+    /// Most markers a `MarkerMeshBuilder` places, so that a spacing far below the line
+    /// strings' lengths fails with `Error::InvalidMarkers` instead of exhausting memory.
+    pub max_markers: usize,
+}
+
+impl Markers {
+    pub const DEFAULT_MAX_MARKERS: usize = 1 << 20;
+
+    /// Markers every `spacing`, the first half a spacing into each line string.
+    pub fn new(shape: MarkerShape, spacing: f64, size: f64) -> Self {
+        Markers {
+            shape,
+            spacing,
+            size,
+            offset: spacing / 2.,
+            max_markers: Self::DEFAULT_MAX_MARKERS,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_max_markers(mut self, max_markers: usize) -> Self {
+        self.max_markers = max_markers;
+        self
+    }
+
+    /// Whether markers can be placed: `spacing` must be finite and positive, and `size` and
+    /// `offset` finite.
+    pub fn is_valid(&self) -> bool {
+        self.spacing.is_finite()
+            && self.spacing > 0.
+            && self.size.is_finite()
+            && self.offset.is_finite()
+    }
+}
+
+/// Builds one mesh of markers placed at regular intervals along line strings, each rotated to
+/// the direction of the segment it is on.
+pub struct MarkerMeshBuilder {
+    markers: Markers,
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    chunks: Chunks,
+    height_source: Option<Arc<dyn HeightSource>>,
+    options: MeshOptions,
+}
+
+impl MarkerMeshBuilder {
+    /// Fails with `Error::InvalidMarkers` unless `markers` is valid.
+    pub fn new(markers: Markers) -> Result<Self, crate::Error> {
+        if !markers.is_valid() {
+            return Err(crate::Error::InvalidMarkers(markers));
+        }
+        Ok(MarkerMeshBuilder {
+            markers,
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            chunks: Chunks::default(),
+            height_source: None,
+            options: MeshOptions::default(),
+        })
+    }
+
+    pub fn with_options(mut self, options: MeshOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Drapes marker vertices onto `height_source`.
+    pub fn with_height_source(mut self, height_source: Arc<dyn HeightSource>) -> Self {
+        self.height_source = Some(height_source);
+        self
+    }
+
+    pub fn add_line_string<LS: LineStringTrait>(
+        &mut self,
+        line_string: &LS,
+    ) -> Result<(), crate::Error>
+    where
+        LS::T: ToPrimitive,
+    {
+        self.add_coords(line_string.coords())
+    }
+
+    pub fn add_line<L: LineTrait>(&mut self, line: &L) -> Result<(), crate::Error>
+    where
+        L::T: ToPrimitive,
+    {
+        self.add_coords(iter::once(line.start()).chain(iter::once(line.end())))
+    }
+
+    /// Adds markers along a line string. Markers on a vertex point along the segment ending
+    /// there.
+    ///
+    /// Fails with `Error::InvalidMarkers`, adding nothing, if the builder would then hold more
+    /// than `Markers::max_markers` markers.
+    pub fn add_coords<I, C>(&mut self, coords: I) -> Result<(), crate::Error>
+    where
+        I: Iterator<Item = C>,
+        C: CoordTrait,
+        C::T: ToPrimitive,
+    {
+        let coords = coords.map(coord_to_xy).collect::<Result<Vec<_>, _>>()?;
+        let spacing = self.markers.spacing;
+        let first = self.markers.offset.max(0.);
+        let length = coords
+            .windows(2)
+            .map(|segment| (segment[1][0] - segment[0][0]).hypot(segment[1][1] - segment[0][1]))
+            .sum::<f64>();
+        let count = match length >= first {
+            true => ((length - first) / spacing).floor() + 1.,
+            false => 0.,
+        };
+        if self.len() as f64 + count > self.markers.max_markers as f64 {
+            return Err(crate::Error::InvalidMarkers(self.markers));
+        }
+
+        let (mut previous, mut travelled) = (None, 0.);
+        let mut next = first;
+        for [x1, y1] in coords {
+            if let Some([x0, y0]) = previous.replace([x1, y1]) {
+                let length = (x1 - x0).hypot(y1 - y0);
+                if length == 0. {
+                    continue;
+                }
+                let direction = [(x1 - x0) / length, (y1 - y0) / length];
+                while next <= travelled + length {
+                    let along = next - travelled;
+                    let xy = [x0 + along * direction[0], y0 + along * direction[1]];
+                    self.push_marker(xy, direction)?;
+                    next += spacing;
+                }
+                travelled += length;
+            }
+        }
+        Ok(())
+    }
+
+    fn push_marker(&mut self, [x, y]: [f64; 2], [cos, sin]: [f64; 2]) -> Result<(), crate::Error> {
+        let (glyph, triangles) = self.markers.shape.glyph();
+        if self.vertices.len() - self.chunks.vertex_start() + glyph.len()
+            > self.options.max_vertices()
+        {
+            self.chunks.start(self.vertices.len(), self.indices.len());
+        }
+
+        // Below `max_vertices`, so they fit the index width.
+        let first = (self.vertices.len() - self.chunks.vertex_start()) as u32;
+        self.indices
+            .extend(triangles.iter().map(|index| first + index));
+        let size = self.markers.size;
+        for [u, v] in glyph {
+            let (u, v) = (u * size, v * size);
+            let (x, y) = (x + u * cos - v * sin, y + u * sin + v * cos);
+            let z = self
+                .height_source
+                .as_ref()
+                .and_then(|height_source| height_source.height_at(x, y))
+                .unwrap_or(0.0);
+            self.vertices.push([
+                x.to_f32().ok_or(crate::Error::CouldNotConvertToF32)?,
+                y.to_f32().ok_or(crate::Error::CouldNotConvertToF32)?,
+                z + self.options.z_offset,
+            ]);
+        }
+        Ok(())
+    }
+
+    /// Removes all markers, keeping the allocated buffers for reuse.
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.indices.clear();
+        self.chunks.clear();
+    }
+
+    /// Number of markers added.
+    pub fn len(&self) -> usize {
+        self.indices.len() / self.markers.shape.glyph().1.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Number of meshes the markers are split into to stay within the index width.
+    pub fn num_meshes(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Builds one mesh per `num_meshes`.
+    pub fn build_meshes(&self) -> Result<Vec<Mesh>, crate::Error> {
+        self.chunks
+            .ranges(self.vertices.len(), self.indices.len())
+            .map(|(vertices, indices)| {
+                crate::polygon::fill_mesh(
+                    self.vertices[vertices].to_vec(),
+                    self.indices[indices].to_vec(),
+                    &self.options,
+                )
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<MarkerMeshBuilder> for Mesh {
+    type Error = crate::Error;
+
+    fn try_from(marker_mesh_builder: MarkerMeshBuilder) -> Result<Self, Self::Error> {
+        if marker_mesh_builder.num_meshes() > 1 {
+            return Err(crate::Error::IndexOverflow);
+        }
+        crate::polygon::fill_mesh(
+            marker_mesh_builder.vertices,
+            marker_mesh_builder.indices,
+            &marker_mesh_builder.options,
+        )
+    }
+}
@@ -0,0 +1,450 @@
+use crate::dash::{DashPattern, DashUnits};
+use crate::line_string::{
+    ATTRIBUTE_LINE_DISTANCE, ATTRIBUTE_LINE_EXTRUSION, ATTRIBUTE_LINE_PROGRESS,
+};
+use crate::options::IndexWidth;
+use crate::polygon::ATTRIBUTE_BOUNDARY_DISTANCE;
+use crate::style::ATTRIBUTE_LINE_WIDTH;
+use bevy::asset::embedded_asset;
+use bevy::mesh::{MeshVertexBufferLayoutRef, PrimitiveTopology};
+use bevy::pbr::{
+    Material, MaterialExtension, MaterialExtensionKey, MaterialExtensionPipeline, MaterialPipeline,
+    MaterialPipelineKey, MaterialPlugin,
+};
+use bevy::prelude::{AlphaMode, App, Asset, LinearRgba, Mesh, Plugin, TypePath, Vec4};
+use bevy::render::render_resource::{
+    AsBindGroup, IndexFormat, RenderPipelineDescriptor, ShaderType, SpecializedMeshPipelineError,
+};
+use bevy::shader::ShaderRef;
+
+const LINE_REVEAL_SHADER_PATH: &str = "embedded://geo_bevy/line_reveal.wgsl";
+const SCREEN_SPACE_LINE_SHADER_PATH: &str = "embedded://geo_bevy/screen_space_line.wgsl";
+const BOUNDARY_GRADIENT_SHADER_PATH: &str = "embedded://geo_bevy/boundary_gradient.wgsl";
+const DASHED_LINE_SHADER_PATH: &str = "embedded://geo_bevy/dashed_line.wgsl";
+
+/// Registers `LineRevealMaterial`.
+pub struct LineRevealPlugin;
+
+impl Plugin for LineRevealPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "line_reveal.wgsl");
+        app.add_plugins(MaterialPlugin::<LineRevealMaterial>::default());
+    }
+}
+
+/// Draws each line string of a mesh built with `MeshOptions::line_distances` up to
+/// `LineReveal::progress`, e.g. to animate a route being traced.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+pub struct LineRevealMaterial {
+    #[uniform(0)]
+    pub reveal: LineReveal,
+    pub alpha_mode: AlphaMode,
+}
+
+#[derive(ShaderType, Clone, Copy, Debug)]
+pub struct LineReveal {
+    pub color: LinearRgba,
+    /// Drawn part of each line string, from 0 for none of it to 1 for all of it.
+    pub progress: f32,
+    /// Part of each line string after `progress` over which the line fades out.
+    pub feather: f32,
+}
+
+impl LineRevealMaterial {
+    pub fn new(color: impl Into<LinearRgba>) -> Self {
+        LineRevealMaterial {
+            reveal: LineReveal {
+                color: color.into(),
+                progress: 1.,
+                feather: 0.,
+            },
+            alpha_mode: AlphaMode::Blend,
+        }
+    }
+
+    pub fn with_progress(mut self, progress: f32) -> Self {
+        self.reveal.progress = progress;
+        self
+    }
+
+    pub fn with_feather(mut self, feather: f32) -> Self {
+        self.reveal.feather = feather;
+        self
+    }
+}
+
+impl Material for LineRevealMaterial {
+    fn vertex_shader() -> ShaderRef {
+        LINE_REVEAL_SHADER_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        LINE_REVEAL_SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.vertex.buffers = vec![layout.0.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            ATTRIBUTE_LINE_PROGRESS.at_shader_location(1),
+        ])?];
+        Ok(())
+    }
+}
+
+/// Registers `ScreenSpaceLineMaterial`.
+pub struct ScreenSpaceLinePlugin;
+
+impl Plugin for ScreenSpaceLinePlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "screen_space_line.wgsl");
+        app.add_plugins(MaterialPlugin::<ScreenSpaceLineMaterial>::default());
+    }
+}
+
+/// Widens meshes built with `LineStyle::ScreenSpace` to `ScreenSpaceLine::width` pixels
+/// whatever the zoom, with anti-aliased edges. Extrusions are projected one geo unit out from
+/// the line, so the width is exact under orthographic projections and approximate under
+/// perspective.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+#[bind_group_data(ScreenSpaceLineKey)]
+pub struct ScreenSpaceLineMaterial {
+    #[uniform(0)]
+    pub line: ScreenSpaceLine,
+    /// Whether to take the color and width of each vertex from `Mesh::ATTRIBUTE_COLOR` and
+    /// `ATTRIBUTE_LINE_WIDTH`, as styled meshes have, rather than from `line`.
+    pub vertex_styles: bool,
+    pub alpha_mode: AlphaMode,
+}
+
+#[derive(ShaderType, Clone, Copy, Debug)]
+pub struct ScreenSpaceLine {
+    pub color: LinearRgba,
+    /// Width in logical pixels, not counting the pixel of anti-aliasing on each side.
+    pub width: f32,
+    /// Alternating on and off lengths in line widths, starting with on, padded with zeros.
+    pub dash_lengths: [Vec4; 2],
+    /// Sum of `dash_lengths`, or 0 for solid lines.
+    pub dash_period: f32,
+    /// Line widths into the dash pattern at which lines start.
+    pub dash_offset: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ScreenSpaceLineKey {
+    vertex_styles: bool,
+    dashed: bool,
+}
+
+impl From<&ScreenSpaceLineMaterial> for ScreenSpaceLineKey {
+    fn from(material: &ScreenSpaceLineMaterial) -> Self {
+        ScreenSpaceLineKey {
+            vertex_styles: material.vertex_styles,
+            dashed: material.line.dash_period > 0.,
+        }
+    }
+}
+
+impl ScreenSpaceLineMaterial {
+    pub fn new(color: impl Into<LinearRgba>, width: f32) -> Self {
+        ScreenSpaceLineMaterial {
+            line: ScreenSpaceLine {
+                color: color.into(),
+                width,
+                dash_lengths: [Vec4::ZERO; 2],
+                dash_period: 0.,
+                dash_offset: 0.,
+            },
+            vertex_styles: false,
+            alpha_mode: AlphaMode::Blend,
+        }
+    }
+
+    pub fn with_vertex_styles(mut self) -> Self {
+        self.vertex_styles = true;
+        self
+    }
+
+    /// Dashes lines built with a `DashUnits::Screen` pattern, in pixels at `line.width`. Dashes
+    /// scale with the width of each vertex when using `vertex_styles`. Fails with
+    /// `Error::InvalidDashPattern` like `DashedLineMaterial::new`, or when `line.width` isn't
+    /// positive.
+    pub fn with_dash(mut self, pattern: &DashPattern) -> Result<Self, crate::Error> {
+        let width = self.line.width;
+        if width.is_nan() || width <= 0. {
+            return Err(crate::Error::InvalidDashPattern(pattern.clone()));
+        }
+        let (lengths, period) = dash_lengths(pattern)?;
+        self.line.dash_lengths = lengths.map(|lengths| lengths / width);
+        self.line.dash_period = period / width;
+        self.line.dash_offset = pattern.offset as f32 / width;
+        Ok(self)
+    }
+}
+
+impl Material for ScreenSpaceLineMaterial {
+    fn vertex_shader() -> ShaderRef {
+        SCREEN_SPACE_LINE_SHADER_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        SCREEN_SPACE_LINE_SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let mut attributes = vec![
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            ATTRIBUTE_LINE_EXTRUSION.at_shader_location(1),
+        ];
+        let mut shader_defs = Vec::new();
+        if key.bind_group_data.vertex_styles {
+            attributes.push(Mesh::ATTRIBUTE_COLOR.at_shader_location(2));
+            attributes.push(ATTRIBUTE_LINE_WIDTH.at_shader_location(3));
+            shader_defs.push("VERTEX_STYLES".into());
+        }
+        if key.bind_group_data.dashed {
+            attributes.push(ATTRIBUTE_LINE_DISTANCE.at_shader_location(4));
+            shader_defs.push("DASHED".into());
+        }
+        descriptor.vertex.buffers = vec![layout.0.get_layout(&attributes)?];
+        descriptor
+            .vertex
+            .shader_defs
+            .extend(shader_defs.iter().cloned());
+        if let Some(fragment) = descriptor.fragment.as_mut() {
+            fragment.shader_defs.extend(shader_defs);
+        }
+        // Quads and joins wind either way depending on the side of the line.
+        descriptor.primitive.cull_mode = None;
+        Ok(())
+    }
+}
+
+/// Registers `BoundaryGradientMaterial`.
+pub struct BoundaryGradientPlugin;
+
+impl Plugin for BoundaryGradientPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "boundary_gradient.wgsl");
+        app.add_plugins(MaterialPlugin::<BoundaryGradientMaterial>::default());
+    }
+}
+
+/// Colors fills built with `MeshOptions::boundary_distances` by their distance from the
+/// polygon's rings, e.g. for inner glows, soft edges or emphasized borders.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+pub struct BoundaryGradientMaterial {
+    #[uniform(0)]
+    pub gradient: BoundaryGradient,
+    pub alpha_mode: AlphaMode,
+}
+
+#[derive(ShaderType, Clone, Copy, Debug)]
+pub struct BoundaryGradient {
+    /// Color on the rings.
+    pub edge_color: LinearRgba,
+    /// Color from `width` inward.
+    pub inner_color: LinearRgba,
+    /// Distance from the rings, in geo units, over which `edge_color` blends into
+    /// `inner_color`.
+    pub width: f32,
+}
+
+impl BoundaryGradientMaterial {
+    pub fn new(
+        edge_color: impl Into<LinearRgba>,
+        inner_color: impl Into<LinearRgba>,
+        width: f32,
+    ) -> Self {
+        BoundaryGradientMaterial {
+            gradient: BoundaryGradient {
+                edge_color: edge_color.into(),
+                inner_color: inner_color.into(),
+                width,
+            },
+            alpha_mode: AlphaMode::Blend,
+        }
+    }
+}
+
+impl Material for BoundaryGradientMaterial {
+    fn vertex_shader() -> ShaderRef {
+        BOUNDARY_GRADIENT_SHADER_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        BOUNDARY_GRADIENT_SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.vertex.buffers = vec![layout.0.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            ATTRIBUTE_BOUNDARY_DISTANCE.at_shader_location(1),
+        ])?];
+        Ok(())
+    }
+}
+
+/// Turns on primitive restart for `LineStrip` meshes holding several line strings, which Bevy's
+/// mesh pipeline otherwise draws joined up, when extending a material as in
+/// `ExtendedMaterial<StandardMaterial, StripRestart>`. `index_width` must be the one the
+/// meshes were built with.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Copy, Debug, Default)]
+#[bind_group_data(IndexWidth)]
+pub struct StripRestart {
+    pub index_width: IndexWidth,
+}
+
+impl From<&StripRestart> for IndexWidth {
+    fn from(strip_restart: &StripRestart) -> Self {
+        strip_restart.index_width
+    }
+}
+
+impl MaterialExtension for StripRestart {
+    fn specialize(
+        _pipeline: &MaterialExtensionPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        key: MaterialExtensionKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        // Only strip topologies may have a strip index format.
+        if key.mesh_key.primitive_topology() == PrimitiveTopology::LineStrip {
+            descriptor.primitive.strip_index_format = Some(match key.bind_group_data {
+                IndexWidth::U16 => IndexFormat::Uint16,
+                IndexWidth::U32 => IndexFormat::Uint32,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Registers `DashedLineMaterial`.
+pub struct DashedLinePlugin;
+
+impl Plugin for DashedLinePlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "dashed_line.wgsl");
+        app.add_plugins(MaterialPlugin::<DashedLineMaterial>::default());
+    }
+}
+
+/// Dashes line meshes built with a `DashUnits::Screen` pattern, keeping dashes the same number
+/// of pixels long whatever the zoom. Lengths are measured along the line on screen, so they are
+/// exact under orthographic projections and approximate under perspective.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug)]
+pub struct DashedLineMaterial {
+    #[uniform(0)]
+    pub line: DashedLine,
+    pub alpha_mode: AlphaMode,
+}
+
+#[derive(ShaderType, Clone, Copy, Debug)]
+pub struct DashedLine {
+    pub color: LinearRgba,
+    /// Alternating on and off lengths in pixels, starting with on, padded with zeros.
+    pub lengths: [Vec4; 2],
+    /// Sum of `lengths`.
+    pub period: f32,
+    /// Pixels into the pattern at which lines start.
+    pub offset: f32,
+}
+
+impl DashedLineMaterial {
+    /// Most lengths a pattern can have, counting odd patterns twice.
+    pub const MAX_LENGTHS: usize = 8;
+
+    /// Fails with `Error::InvalidDashPattern` for invalid patterns, patterns in geo units and
+    /// patterns with more than `MAX_LENGTHS` lengths.
+    pub fn new(color: impl Into<LinearRgba>, pattern: &DashPattern) -> Result<Self, crate::Error> {
+        let (lengths, period) = dash_lengths(pattern)?;
+        Ok(DashedLineMaterial {
+            line: DashedLine {
+                color: color.into(),
+                lengths,
+                period,
+                offset: pattern.offset as f32,
+            },
+            alpha_mode: AlphaMode::Blend,
+        })
+    }
+}
+
+/// The lengths of a `DashUnits::Screen` pattern, with odd patterns repeated and padded with
+/// zeros to `DashedLineMaterial::MAX_LENGTHS`, and their sum.
+fn dash_lengths(pattern: &DashPattern) -> Result<([Vec4; 2], f32), crate::Error> {
+    let mut lengths = pattern.lengths.clone();
+    if lengths.len() % 2 == 1 {
+        lengths.extend_from_within(..);
+    }
+    if !pattern.is_valid()
+        || pattern.units != DashUnits::Screen
+        || lengths.len() > DashedLineMaterial::MAX_LENGTHS
+    {
+        return Err(crate::Error::InvalidDashPattern(pattern.clone()));
+    }
+    let mut padded = [0.; DashedLineMaterial::MAX_LENGTHS];
+    for (padded, length) in padded.iter_mut().zip(&lengths) {
+        *padded = *length as f32;
+    }
+    Ok((
+        [
+            Vec4::from_slice(&padded[..4]),
+            Vec4::from_slice(&padded[4..]),
+        ],
+        lengths.iter().sum::<f64>() as f32,
+    ))
+}
+
+impl Material for DashedLineMaterial {
+    fn vertex_shader() -> ShaderRef {
+        DASHED_LINE_SHADER_PATH.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        DASHED_LINE_SHADER_PATH.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.vertex.buffers = vec![layout.0.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            ATTRIBUTE_LINE_DISTANCE.at_shader_location(1),
+        ])?];
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use crate::dash::{DashPattern, DashUnits};
+use crate::pattern::FillPattern;
+use bevy::{asset::RenderAssetUsages, mesh::Indices, render::render_resource::PrimitiveTopology};
+
+/// Width of the index buffer of generated meshes. Builders start a new mesh when one would have
+/// more vertices than the width can address.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum IndexWidth {
+    U16,
+    #[default]
+    U32,
+}
+
+/// Which meshes polygons produce.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PolygonMode {
+    Fill,
+    Outline,
+    #[default]
+    FillAndOutline,
+}
+
+impl PolygonMode {
+    pub(crate) fn fill(self) -> bool {
+        self != PolygonMode::Outline
+    }
+
+    pub(crate) fn outline(self) -> bool {
+        self != PolygonMode::Fill
+    }
+}
+
+/// How polygon outlines are split into meshes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutlineGrouping {
+    /// `PolygonMesh::exterior_mesh` plus one `PolygonMesh::interior_meshes` entry per hole.
+    #[default]
+    PerRing,
+    /// Every ring in `PolygonMeshParts::outline_mesh`.
+    Combined,
+}
+
+/// How polygon, rect and triangle outlines are closed. Rings may be given with or without their
+/// first coordinate repeated at the end; fills never get the repeated vertex.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RingClosure {
+    /// The last vertex is connected back to the first by index: `LineList` outlines get a
+    /// closing segment and `LineStrip` outlines end with the first vertex's index.
+    #[default]
+    Indexed,
+    /// Outlines end with a copy of their first vertex, so the closing segment has its own
+    /// vertices, e.g. for per-vertex attributes along the line.
+    Duplicated,
+}
+
+/// How line strings and outlines are meshed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineStyle {
+    /// Vertices on the line, connected according to `MeshOptions::topology`.
+    #[default]
+    Lines,
+    /// A `TriangleList` of zero-width quads along the line, with `ATTRIBUTE_LINE_EXTRUSION`, for
+    /// a material to widen to a constant width on screen. Consecutive segments are joined with
+    /// a bevel. `MeshOptions::topology` is ignored.
+    ScreenSpace,
+}
+
+/// Choices made when turning geometries into meshes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshOptions {
+    /// Topology of line string and outline meshes: `LineList`, `LineStrip` (line strings are
+    /// separated by primitive restart indices, which Bevy's mesh pipeline only honours with a
+    /// material that sets the strip index format, such as one extended with `StripRestart`) or
+    /// `PointList`. Fills are always `TriangleList`.
+    pub topology: PrimitiveTopology,
+    pub asset_usages: RenderAssetUsages,
+    pub index_width: IndexWidth,
+    /// Added to the z of every vertex, after any height source.
+    pub z_offset: f32,
+    /// Whether to add `Mesh::ATTRIBUTE_NORMAL`. Fills get upward normals, lines get zeros.
+    pub normals: bool,
+    /// Whether to add `Mesh::ATTRIBUTE_UV_0`. Fills get their x and y as world-space UVs,
+    /// lines get zeros.
+    pub uvs: bool,
+    /// Whether `multi_line_string_to_mesh_with_options` puts every line string in one mesh
+    /// rather than building a mesh per line string. Merged meshes are only split to stay within
+    /// `index_width`.
+    pub merge_line_strings: bool,
+    pub polygon_mode: PolygonMode,
+    pub outline_grouping: OutlineGrouping,
+    pub ring_closure: RingClosure,
+    /// Dashes line strings and outlines. Lines are solid when `None`.
+    pub dash: Option<DashPattern>,
+    /// Whether line string and outline meshes get `ATTRIBUTE_LINE_DISTANCE` and
+    /// `ATTRIBUTE_LINE_PROGRESS`. Outlines are then closed with a duplicated vertex.
+    pub line_distances: bool,
+    pub line_style: LineStyle,
+    /// Draws polygon, rect and triangle fills as a pattern. Fills are solid when `None`.
+    pub fill_pattern: Option<FillPattern>,
+    /// Adds `ATTRIBUTE_BOUNDARY_DISTANCE` to fills, first refining them to have no edge
+    /// longer than this, so that there are vertices away from the rings to carry distances.
+    pub boundary_distances: Option<f64>,
+}
+
+impl Default for MeshOptions {
+    fn default() -> Self {
+        MeshOptions {
+            topology: PrimitiveTopology::LineList,
+            asset_usages: RenderAssetUsages::default(),
+            index_width: IndexWidth::default(),
+            z_offset: 0.0,
+            normals: true,
+            uvs: true,
+            merge_line_strings: false,
+            polygon_mode: PolygonMode::default(),
+            outline_grouping: OutlineGrouping::default(),
+            ring_closure: RingClosure::default(),
+            dash: None,
+            line_distances: false,
+            line_style: LineStyle::default(),
+            fill_pattern: None,
+            boundary_distances: None,
+        }
+    }
+}
+
+/// Index that separates line strips when `topology` is `LineStrip`.
+pub(crate) const STRIP_RESTART: u32 = u32::MAX;
+
+impl MeshOptions {
+    /// Most vertices a single mesh can have before builders start a new one.
+    pub(crate) fn max_vertices(&self) -> usize {
+        match self.index_width {
+            // The largest index is reserved for primitive restart.
+            IndexWidth::U16 => usize::from(u16::MAX),
+            IndexWidth::U32 => u32::MAX as usize,
+        }
+    }
+
+    /// Topology line builders index vertices with, before any extrusion.
+    pub(crate) fn line_topology(&self) -> PrimitiveTopology {
+        match self.line_style {
+            LineStyle::Lines => self.topology,
+            LineStyle::ScreenSpace => PrimitiveTopology::LineList,
+        }
+    }
+
+    /// Most line vertices a single mesh can have, leaving room for every vertex to be extruded
+    /// to both sides of two segments plus a join.
+    pub(crate) fn max_line_vertices(&self) -> usize {
+        match self.line_style {
+            LineStyle::Lines => self.max_vertices(),
+            LineStyle::ScreenSpace => self.max_vertices() / 5,
+        }
+    }
+
+    /// Whether line meshes get `ATTRIBUTE_LINE_DISTANCE` and `ATTRIBUTE_LINE_PROGRESS`.
+    pub(crate) fn adds_line_distances(&self) -> bool {
+        self.line_distances || matches!(&self.dash, Some(dash) if dash.units == DashUnits::Screen)
+    }
+
+    /// Converts indices collected as `u32` to `index_width`.
+    pub(crate) fn indices(
+        &self,
+        indices: Vec<u32>,
+        num_vertices: usize,
+    ) -> Result<Indices, crate::Error> {
+        match self.index_width {
+            IndexWidth::U32 => Ok(Indices::U32(indices)),
+            // `u16::MAX` is reserved for primitive restart.
+            IndexWidth::U16 if num_vertices <= usize::from(u16::MAX) => Ok(Indices::U16(
+                indices
+                    .into_iter()
+                    .map(|index| match index {
+                        STRIP_RESTART => u16::MAX,
+                        index => index as u16,
+                    })
+                    .collect(),
+            )),
+            IndexWidth::U16 => Err(crate::Error::IndexOverflow),
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+type Vertex = [f32; 3]; // [x, y, z]
+
+/// Shapes a `FillPattern` repeats.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PatternKind {
+    /// Parallel lines.
+    #[default]
+    Hatch,
+    /// Two sets of parallel lines at right angles. Where they cross, only the first set is
+    /// drawn, so translucent patterns don't blend twice.
+    CrossHatch,
+    /// Round dots on a square grid.
+    Dots,
+}
+
+/// Lines or dots drawn instead of a solid fill, clipped to each polygon and its holes. Patterns
+/// are laid out from the origin, so they line up across neighbouring polygons.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FillPattern {
+    pub kind: PatternKind,
+    /// Direction of the lines, or of the rows of dots, in radians counterclockwise from the x
+    /// axis.
+    pub angle: f64,
+    /// Distance between the centers of neighbouring lines or dots.
+    pub spacing: f64,
+    /// Width of the lines, or diameter of the dots.
+    pub width: f64,
+}
+
+impl FillPattern {
+    pub fn hatch(angle: f64, spacing: f64, width: f64) -> Self {
+        FillPattern {
+            kind: PatternKind::Hatch,
+            angle,
+            spacing,
+            width,
+        }
+    }
+
+    pub fn cross_hatch(angle: f64, spacing: f64, width: f64) -> Self {
+        FillPattern {
+            kind: PatternKind::CrossHatch,
+            angle,
+            spacing,
+            width,
+        }
+    }
+
+    pub fn dots(angle: f64, spacing: f64, diameter: f64) -> Self {
+        FillPattern {
+            kind: PatternKind::Dots,
+            angle,
+            spacing,
+            width: diameter,
+        }
+    }
+
+    /// Whether the pattern can be applied: the angle must be finite and the spacing and width
+    /// finite and positive. Meshing fails with `Error::InvalidFillPattern` otherwise.
+    pub fn is_valid(&self) -> bool {
+        self.angle.is_finite()
+            && self.spacing.is_finite()
+            && self.spacing > 0.
+            && self.width.is_finite()
+            && self.width > 0.
+    }
+
+    /// The pattern's triangles within the fill triangles given by `vertices` and
+    /// `triangle_indices`, facing the same way. Heights are interpolated across each triangle.
+    pub(crate) fn clip(
+        &self,
+        vertices: &[Vertex],
+        triangle_indices: &[usize],
+    ) -> (Vec<Vertex>, Vec<usize>) {
+        let mut clipped = Clipped::default();
+        match self.kind {
+            PatternKind::Hatch => {
+                self.clip_lines(self.angle, false, vertices, triangle_indices, &mut clipped)
+            }
+            PatternKind::CrossHatch => {
+                self.clip_lines(self.angle, false, vertices, triangle_indices, &mut clipped);
+                self.clip_lines(
+                    self.angle + FRAC_PI_2,
+                    true,
+                    vertices,
+                    triangle_indices,
+                    &mut clipped,
+                );
+            }
+            PatternKind::Dots => self.clip_dots(vertices, triangle_indices, &mut clipped),
+        }
+        (clipped.vertices, clipped.indices)
+    }
+
+    /// Clips bands `width` wide, centered on lines `spacing` apart, to each triangle. With
+    /// `skip_crossings`, bands stop short of the bands at right angles to them.
+    fn clip_lines(
+        &self,
+        angle: f64,
+        skip_crossings: bool,
+        vertices: &[Vertex],
+        triangle_indices: &[usize],
+        clipped: &mut Clipped,
+    ) {
+        let frame = Frame::new(angle);
+        let half_width = self.width / 2.;
+        for triangle in triangle_indices.chunks_exact(3) {
+            let Some(triangle) =
+                Triangle::new(&frame, triangle.iter().map(|index| vertices[*index]))
+            else {
+                continue;
+            };
+            let (low, high) = triangle.v_range();
+            let first = ((low - half_width) / self.spacing).ceil() as i64;
+            let last = ((high + half_width) / self.spacing).floor() as i64;
+            for line in first..=last {
+                let center = line as f64 * self.spacing;
+                let band = clip(&triangle.uv, |[_, v]| v - (center - half_width));
+                let band = clip(&band, |[_, v]| center + half_width - v);
+                if !skip_crossings {
+                    clipped.push(&frame, &triangle, &band);
+                    continue;
+                }
+                // The gaps between crossing bands, from the end of one to the start of the next.
+                let (low, high) = triangle.u_range();
+                let first = ((low + half_width) / self.spacing).floor() as i64 - 1;
+                let last = ((high - half_width) / self.spacing).ceil() as i64;
+                for crossing in first..=last {
+                    let start = crossing as f64 * self.spacing + half_width;
+                    let end = start + self.spacing - self.width;
+                    if end > start {
+                        let gap = clip(&band, |[u, _]| u - start);
+                        clipped.push(&frame, &triangle, &clip(&gap, |[u, _]| end - u));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Clips octagonal dots, on a grid `spacing` apart, to each triangle.
+    fn clip_dots(&self, vertices: &[Vertex], triangle_indices: &[usize], clipped: &mut Clipped) {
+        let frame = Frame::new(self.angle);
+        // The octagon's inradius is `width / 2`.
+        let radius = self.width / 2. / (TAU / 16.).cos();
+        let mut dot = (0..8)
+            .map(|corner| {
+                let angle = (2 * corner + 1) as f64 * TAU / 16.;
+                [radius * angle.cos(), radius * angle.sin()]
+            })
+            .collect::<Vec<_>>();
+        let mut dot_clockwise = false;
+        for triangle in triangle_indices.chunks_exact(3) {
+            let Some(triangle) =
+                Triangle::new(&frame, triangle.iter().map(|index| vertices[*index]))
+            else {
+                continue;
+            };
+            let (u_low, u_high) = triangle.u_range();
+            let (v_low, v_high) = triangle.v_range();
+            if triangle.clockwise() != dot_clockwise {
+                dot.reverse();
+                dot_clockwise = !dot_clockwise;
+            }
+            let cells = |low: f64, high: f64| {
+                ((low - radius) / self.spacing).ceil() as i64
+                    ..=((high + radius) / self.spacing).floor() as i64
+            };
+            for row in cells(v_low, v_high) {
+                for column in cells(u_low, u_high) {
+                    let (u, v) = (column as f64 * self.spacing, row as f64 * self.spacing);
+                    let mut shape = dot
+                        .iter()
+                        .map(|[du, dv]| [u + du, v + dv])
+                        .collect::<Vec<_>>();
+                    for edge in 0..3 {
+                        shape = clip(&shape, |point| triangle.inside(edge, point));
+                    }
+                    clipped.push(&frame, &triangle, &shape);
+                }
+            }
+        }
+    }
+}
+
+/// Coordinates `u` along the pattern's lines and `v` across them.
+struct Frame {
+    cos: f64,
+    sin: f64,
+}
+
+impl Frame {
+    fn new(angle: f64) -> Self {
+        Frame {
+            cos: angle.cos(),
+            sin: angle.sin(),
+        }
+    }
+
+    fn to_uv(&self, [x, y]: [f64; 2]) -> [f64; 2] {
+        [x * self.cos + y * self.sin, y * self.cos - x * self.sin]
+    }
+
+    fn to_xy(&self, [u, v]: [f64; 2]) -> [f64; 2] {
+        [u * self.cos - v * self.sin, u * self.sin + v * self.cos]
+    }
+}
+
+/// A fill triangle in pattern coordinates.
+struct Triangle {
+    uv: Vec<[f64; 2]>,
+    z: [f64; 3],
+    /// Twice the signed area: positive when counterclockwise.
+    area: f64,
+}
+
+impl Triangle {
+    /// `None` for degenerate triangles.
+    fn new(frame: &Frame, corners: impl Iterator<Item = Vertex>) -> Option<Self> {
+        let (mut uv, mut z) = (Vec::with_capacity(3), [0.; 3]);
+        for (corner, [x, y, height]) in corners.enumerate() {
+            uv.push(frame.to_uv([f64::from(x), f64::from(y)]));
+            z[corner] = f64::from(height);
+        }
+        let area = cross(uv[0], uv[1], uv[2]);
+        (area != 0.).then_some(Triangle { uv, z, area })
+    }
+
+    fn clockwise(&self) -> bool {
+        self.area < 0.
+    }
+
+    fn u_range(&self) -> (f64, f64) {
+        let us = self.uv.iter().map(|[u, _]| *u);
+        (
+            us.clone().fold(f64::INFINITY, f64::min),
+            us.fold(f64::NEG_INFINITY, f64::max),
+        )
+    }
+
+    fn v_range(&self) -> (f64, f64) {
+        let vs = self.uv.iter().map(|[_, v]| *v);
+        (
+            vs.clone().fold(f64::INFINITY, f64::min),
+            vs.fold(f64::NEG_INFINITY, f64::max),
+        )
+    }
+
+    /// How far inside edge `edge` `point` is, scaled by the edge's length.
+    fn inside(&self, edge: usize, point: [f64; 2]) -> f64 {
+        cross(self.uv[edge], self.uv[(edge + 1) % 3], point) * self.area.signum()
+    }
+
+    /// The height at `point`, from the corners' barycentric weights.
+    fn z_at(&self, point: [f64; 2]) -> f64 {
+        (0..3)
+            .map(|corner| {
+                let weight =
+                    cross(self.uv[(corner + 1) % 3], self.uv[(corner + 2) % 3], point) / self.area;
+                weight * self.z[corner]
+            })
+            .sum()
+    }
+}
+
+#[derive(Default)]
+struct Clipped {
+    vertices: Vec<Vertex>,
+    indices: Vec<usize>,
+}
+
+impl Clipped {
+    /// Appends a convex polygon clipped from `triangle`, as a fan.
+    fn push(&mut self, frame: &Frame, triangle: &Triangle, polygon: &[[f64; 2]]) {
+        if polygon.len() < 3 {
+            return;
+        }
+        let start = self.vertices.len();
+        self.vertices.extend(polygon.iter().map(|uv| {
+            let [x, y] = frame.to_xy(*uv);
+            [x as f32, y as f32, triangle.z_at(*uv) as f32]
+        }));
+        for corner in start + 1..self.vertices.len() - 1 {
+            self.indices.extend([start, corner, corner + 1]);
+        }
+    }
+}
+
+/// Twice the signed area of triangle `a`, `b`, `c`.
+fn cross(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// The part of convex `polygon` where `distance` isn't negative, keeping its winding.
+fn clip(polygon: &[[f64; 2]], distance: impl Fn([f64; 2]) -> f64) -> Vec<[f64; 2]> {
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for (index, &current) in polygon.iter().enumerate() {
+        let next = polygon[(index + 1) % polygon.len()];
+        let (from, to) = (distance(current), distance(next));
+        if from >= 0. {
+            clipped.push(current);
+        }
+        if (from >= 0.) != (to >= 0.) {
+            let t = from / (from - to);
+            clipped.push([
+                current[0] + (next[0] - current[0]) * t,
+                current[1] + (next[1] - current[1]) * t,
+            ]);
+        }
+    }
+    clipped
+}
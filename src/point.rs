@@ -1,24 +1,195 @@
-use geo_traits::CoordTrait;
+use crate::options::MeshOptions;
+use crate::style::FeatureStyle;
+use bevy::color::{ColorToComponents, LinearRgba};
+use bevy::math::{Quat, Vec2, Vec3};
+use bevy::transform::components::Transform;
+use geo_traits::{CoordTrait, GeometryTrait, MultiPointTrait, PointTrait};
 use num_traits::ToPrimitive;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct SpritePosition {
     pub x: f32,
     pub y: f32,
+    /// The coordinate's z, or 0 for 2D coordinates.
+    pub z: f32,
+    /// Index of the feature the point came from. See `PointMeshBuilder::start_feature`.
+    pub feature: usize,
+    /// Styling of this point, overriding the layer's.
+    pub size: Option<f32>,
+    /// Counter-clockwise, in radians.
+    pub rotation: Option<f32>,
+    pub color: Option<LinearRgba>,
 }
 
-#[derive(Default)]
+impl SpritePosition {
+    pub fn new(x: f32, y: f32) -> Self {
+        SpritePosition {
+            x,
+            y,
+            z: 0.,
+            feature: 0,
+            size: None,
+            rotation: None,
+            color: None,
+        }
+    }
+}
+
+impl From<SpritePosition> for Vec2 {
+    fn from(position: SpritePosition) -> Self {
+        Vec2::new(position.x, position.y)
+    }
+}
+
+impl From<SpritePosition> for Vec3 {
+    fn from(position: SpritePosition) -> Self {
+        Vec3::new(position.x, position.y, position.z)
+    }
+}
+
+/// Rotated about z and uniformly scaled by the point's rotation and size, if any.
+impl From<SpritePosition> for Transform {
+    fn from(position: SpritePosition) -> Self {
+        Transform {
+            translation: position.into(),
+            rotation: Quat::from_rotation_z(position.rotation.unwrap_or(0.)),
+            scale: Vec3::splat(position.size.unwrap_or(1.)),
+        }
+    }
+}
+
+/// Per-point data of an instanced point layer, laid out as its instance buffer.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointInstance {
+    pub position: [f32; 3],
+    /// Scale applied to the shared marker mesh.
+    pub size: f32,
+    /// Linear RGBA.
+    pub color: [f32; 4],
+    /// Counter-clockwise, in radians.
+    pub rotation: f32,
+}
+
+impl PointInstance {
+    /// An instance at `position`, taking its size, color and rotation if it has them.
+    pub fn new(position: SpritePosition, size: f32, color: impl Into<LinearRgba>) -> Self {
+        PointInstance {
+            position: Vec3::from(position).to_array(),
+            size: position.size.unwrap_or(size),
+            color: position.color.unwrap_or(color.into()).to_f32_array(),
+            rotation: position.rotation.unwrap_or(0.),
+        }
+    }
 
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+}
+
+#[derive(Default)]
 pub struct PointMeshBuilder {
     points: Vec<SpritePosition>,
+    /// Number of features started.
+    features: usize,
+    style: Option<FeatureStyle>,
+    options: MeshOptions,
 }
 
 impl PointMeshBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Of the options, only `z_offset` applies to points.
+    pub fn with_options(mut self, options: MeshOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Adds the point's coordinate. Empty points are skipped.
+    pub fn add_point(&mut self, point: &impl PointTrait<T: ToPrimitive>) {
+        if let Some(coord) = point.coord() {
+            self.add_coord(coord);
+        }
+    }
+
+    pub fn add_multi_point(&mut self, multi_point: &impl MultiPointTrait<T: ToPrimitive>) {
+        for point in multi_point.points() {
+            self.add_point(&point);
+        }
+    }
+
     /// Call for `add_earcutr_input` for each polygon you want to add to the mesh.
     pub fn add_coord(&mut self, coord: impl CoordTrait<T: ToPrimitive>) {
-        self.points.push(SpritePosition {
-            x: coord.x().to_f32().unwrap(),
-            y: coord.y().to_f32().unwrap(),
-        });
+        let mut point = SpritePosition {
+            z: coord.nth(2).and_then(|z| z.to_f32()).unwrap_or(0.) + self.options.z_offset,
+            feature: self.features.saturating_sub(1),
+            ..SpritePosition::new(coord.x().to_f32().unwrap(), coord.y().to_f32().unwrap())
+        };
+        if let Some(style) = &self.style {
+            point.z += style.z_order;
+            point.size = Some(style.point_size);
+            point.color = Some(style.fill_color);
+        }
+        self.points.push(point);
+    }
+
+    /// Gives points added from now on the style's point size and fill color, and raises them by
+    /// its z order.
+    pub fn set_style(&mut self, style: &FeatureStyle) {
+        self.style = Some(*style);
+    }
+
+    /// Tags points added from now on with the next feature index, starting from 0. Points added
+    /// before any feature is started are tagged 0 too.
+    pub fn start_feature(&mut self) {
+        self.features += 1;
+    }
+
+    /// The points added so far, to be styled before building.
+    pub fn points_mut(&mut self) -> &mut [SpritePosition] {
+        &mut self.points
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.points.reserve(additional);
+    }
+
+    /// Reserves room for the points in `geometry`.
+    pub fn reserve_for(&mut self, geometry: &impl GeometryTrait) {
+        self.reserve(crate::build_mesh::Counts::of(geometry).points);
+    }
+
+    /// Removes all points, keeping the allocated buffer for reuse.
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.features = 0;
+        self.style = None;
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Number of points the builder can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.points.capacity()
+    }
+
+    /// An instance per point, all of the same `size` and `color`, to be restyled individually
+    /// if needed.
+    pub fn instances(&self, size: f32, color: impl Into<LinearRgba>) -> Vec<PointInstance> {
+        let color = color.into();
+        self.points
+            .iter()
+            .map(|position| PointInstance::new(*position, size, color))
+            .collect()
     }
 }
 
@@ -30,4 +201,12 @@ impl crate::build_mesh::BuildMesh for PointMeshBuilder {
             Ok(crate::GeometryMesh::Point(self.points))
         }
     }
+
+    fn build_ref(&self) -> Result<crate::GeometryMesh, crate::Error> {
+        if self.points.is_empty() {
+            Err(crate::Error::EmptyGeometry)
+        } else {
+            Ok(crate::GeometryMesh::Point(self.points.clone()))
+        }
+    }
 }
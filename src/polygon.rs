@@ -1,17 +1,163 @@
-use crate::line_string::LineStringMeshBuilder;
+use crate::boundary::Boundary;
+use crate::build_mesh::Counts;
+use crate::chunks::Chunks;
+use crate::height::HeightSource;
+use crate::line_string::{apply_z_order, LineStringMeshBuilder};
+use crate::options::{MeshOptions, OutlineGrouping};
+use crate::style::{FeatureStyle, StyleSpans};
+use crate::triangulate::{
+    EarcutrTriangulator, Refinement, Triangulation, TriangulationInput, Triangulator,
+};
+use crate::validation::{Validation, ValidationReport};
+use bevy::color::ColorToComponents;
+use bevy::mesh::{Indices, MeshVertexAttribute, VertexFormat};
 use bevy::prelude::Mesh;
 use geo_traits::*;
+use std::ops::Range;
+use std::sync::Arc;
 
+type Vertex = [f32; 3]; // [x, y, z]
+
+/// Distance of each fill vertex from the nearest ring of its polygon, in geo units. Added with
+/// `MeshOptions::boundary_distances`.
+pub const ATTRIBUTE_BOUNDARY_DISTANCE: MeshVertexAttribute = MeshVertexAttribute::new(
+    "Vertex_BoundaryDistance",
+    0x6765_6f62_6576_7905,
+    VertexFormat::Float32,
+);
+
+/// The fill and outlines of a polygon, as built with the default `PolygonMode::FillAndOutline`
+/// and `OutlineGrouping::PerRing`.
 pub struct PolygonMesh {
     pub mesh: Mesh,
     pub exterior_mesh: Mesh,
     pub interior_meshes: Vec<Mesh>,
 }
 
+/// The meshes of a polygon built with any `MeshOptions`. Which are present depends on
+/// `MeshOptions::polygon_mode` and `MeshOptions::outline_grouping`.
+pub struct PolygonMeshParts {
+    /// The fill.
+    pub mesh: Option<Mesh>,
+    pub exterior_mesh: Option<Mesh>,
+    pub interior_meshes: Vec<Mesh>,
+    /// The exterior and interior outlines together.
+    pub outline_mesh: Option<Mesh>,
+}
+
+/// Fails with `Error::CouldNotBuildMesh` unless there is a fill and an exterior outline.
+impl TryFrom<PolygonMeshParts> for PolygonMesh {
+    type Error = crate::Error;
+
+    fn try_from(parts: PolygonMeshParts) -> Result<Self, Self::Error> {
+        match parts {
+            PolygonMeshParts {
+                mesh: Some(mesh),
+                exterior_mesh: Some(exterior_mesh),
+                interior_meshes,
+                outline_mesh: None,
+            } => Ok(PolygonMesh {
+                mesh,
+                exterior_mesh,
+                interior_meshes,
+            }),
+            _ => Err(crate::Error::CouldNotBuildMesh),
+        }
+    }
+}
+
+impl From<PolygonMesh> for PolygonMeshParts {
+    fn from(polygon_mesh: PolygonMesh) -> Self {
+        PolygonMeshParts {
+            mesh: Some(polygon_mesh.mesh),
+            exterior_mesh: Some(polygon_mesh.exterior_mesh),
+            interior_meshes: polygon_mesh.interior_meshes,
+            outline_mesh: None,
+        }
+    }
+}
+
+/// `GeometryMesh::Polygon` when the parts make up a `PolygonMesh`, `GeometryMesh::PolygonParts`
+/// otherwise.
+impl From<PolygonMeshParts> for crate::GeometryMesh {
+    fn from(parts: PolygonMeshParts) -> Self {
+        match parts {
+            PolygonMeshParts {
+                mesh: Some(mesh),
+                exterior_mesh: Some(exterior_mesh),
+                interior_meshes,
+                outline_mesh: None,
+            } => crate::GeometryMesh::Polygon(PolygonMesh {
+                mesh,
+                exterior_mesh,
+                interior_meshes,
+            }),
+            parts => crate::GeometryMesh::PolygonParts(parts),
+        }
+    }
+}
+
+/// The polygons of a multi-polygon in one fill mesh and one outline mesh.
+pub struct MultiPolygonMesh {
+    pub mesh: Option<Mesh>,
+    pub outline_mesh: Option<Mesh>,
+    /// Where each polygon's triangles and outline are in the index buffers.
+    pub ranges: Vec<PolygonIndexRanges>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolygonIndexRanges {
+    pub fill: Range<usize>,
+    pub outline: Range<usize>,
+}
+
+impl MultiPolygonMesh {
+    /// Copies of the meshes drawing only polygon `index`. The vertex buffers are copied whole.
+    pub fn part(&self, index: usize) -> Option<PolygonMeshParts> {
+        let ranges = self.ranges.get(index)?;
+        Some(PolygonMeshParts {
+            mesh: self
+                .mesh
+                .as_ref()
+                .map(|mesh| with_index_range(mesh, ranges.fill.clone())),
+            exterior_mesh: None,
+            interior_meshes: Vec::new(),
+            outline_mesh: self
+                .outline_mesh
+                .as_ref()
+                .map(|mesh| with_index_range(mesh, ranges.outline.clone())),
+        })
+    }
+}
+
+fn with_index_range(mesh: &Mesh, range: Range<usize>) -> Mesh {
+    let mut mesh = mesh.clone();
+    let indices = match mesh.indices() {
+        Some(Indices::U16(indices)) => Indices::U16(indices[range].to_vec()),
+        Some(Indices::U32(indices)) => Indices::U32(indices[range].to_vec()),
+        None => return mesh,
+    };
+    mesh.insert_indices(indices);
+    mesh
+}
+
 pub struct PolygonMeshBuilder<Scalar: geo_types::CoordFloat> {
-    polygon: bevy_earcutr::PolygonMeshBuilder<Scalar>,
+    triangulator: Box<dyn Triangulator<Scalar>>,
+    refinement: Option<Refinement>,
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    /// Boundary distance of each vertex, when `MeshOptions::boundary_distances` is set.
+    distances: Vec<f32>,
+    chunks: Chunks,
+    /// Exterior outlines, or all outlines when they are combined.
     exterior: LineStringMeshBuilder,
     interiors: Vec<LineStringMeshBuilder>,
+    styles: StyleSpans,
+    /// Style of polygons added from now on, for new interior outline builders.
+    style: Option<FeatureStyle>,
+    validation: Validation,
+    height_source: Option<Arc<dyn HeightSource>>,
+    options: MeshOptions,
 }
 
 impl<Scalar: geo_types::CoordFloat> Default for PolygonMeshBuilder<Scalar> {
@@ -23,92 +169,512 @@ impl<Scalar: geo_types::CoordFloat> Default for PolygonMeshBuilder<Scalar> {
 impl<Scalar: geo_types::CoordFloat> PolygonMeshBuilder<Scalar> {
     pub fn new() -> Self {
         Self {
-            polygon: bevy_earcutr::PolygonMeshBuilder::default(),
+            triangulator: Box::new(EarcutrTriangulator),
+            refinement: None,
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            distances: Vec::new(),
+            chunks: Chunks::default(),
             exterior: LineStringMeshBuilder::default(),
             interiors: Vec::new(),
+            styles: StyleSpans::default(),
+            style: None,
+            validation: Validation::Off,
+            height_source: None,
+            options: MeshOptions::default(),
         }
     }
 
+    pub fn with_options(mut self, options: MeshOptions) -> Self {
+        self.exterior = self.exterior.with_options(options.clone());
+        self.options = options;
+        self
+    }
+
+    /// Replaces the default earcutr backend used for the fill mesh.
+    pub fn with_triangulator(mut self, triangulator: impl Triangulator<Scalar> + 'static) -> Self {
+        self.triangulator = Box::new(triangulator);
+        self
+    }
+
+    /// Subdivides fill triangles until they are within `refinement`'s limits. See
+    /// `Triangulation::refine`.
+    pub fn with_refinement(mut self, refinement: Refinement) -> Self {
+        self.refinement = Some(refinement);
+        self
+    }
+
+    /// Drapes the fill and outlines onto `height_source`. Only outlines are resampled, so the
+    /// fill follows the terrain only as closely as its vertices allow: combine with
+    /// `with_refinement` for fills spanning more than a few samples.
+    pub fn with_height_source(mut self, height_source: Arc<dyn HeightSource>) -> Self {
+        self.exterior = self.exterior.with_height_source(height_source.clone());
+        self.height_source = Some(height_source);
+        self
+    }
+
+    pub fn with_validation(mut self, validation: Validation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    /// Styles polygons added from now on. Once a style is set, fills get
+    /// `Mesh::ATTRIBUTE_COLOR`, and outlines are styled as by `LineStringMeshBuilder::set_style`.
+    pub fn set_style(&mut self, style: &FeatureStyle) {
+        self.styles.start(self.vertices.len(), *style);
+        self.exterior.set_style(style);
+        self.style = Some(*style);
+    }
+
+    /// Removes all polygons, keeping the allocated buffers for reuse.
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.indices.clear();
+        self.distances.clear();
+        self.chunks.clear();
+        self.exterior.clear();
+        self.interiors.clear();
+        self.styles.clear();
+        self.style = None;
+    }
+
+    /// Number of fill vertices added so far.
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty() && self.exterior.is_empty()
+    }
+
+    /// Number of fill vertices the builder can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.vertices.capacity()
+    }
+
     pub fn add_polygon(
         &mut self,
         polygon: &impl geo_traits::PolygonTrait<T = Scalar>,
     ) -> Result<(), crate::Error> {
-        self.polygon
-            .add_earcutr_input(Self::polygon_to_earcutr_input(polygon));
+        self.add_polygon_with_report(polygon).map(drop)
+    }
+
+    /// Like `add_polygon`, but also returns what validation found in `polygon`. The report is
+    /// empty when validation is off.
+    pub fn add_polygon_with_report(
+        &mut self,
+        polygon: &impl geo_traits::PolygonTrait<T = Scalar>,
+    ) -> Result<ValidationReport, crate::Error> {
+        match self.validation {
+            Validation::Off => {
+                self.add_valid_polygon(polygon)?;
+                Ok(ValidationReport::default())
+            }
+            Validation::Reject => {
+                let report = crate::validation::validate_polygon(polygon);
+                if !report.is_valid() {
+                    return Err(crate::Error::InvalidGeometry(report));
+                }
+                self.add_valid_polygon(polygon)?;
+                Ok(report)
+            }
+            Validation::Repair => {
+                let (repaired, report) = crate::validation::repair_polygon(polygon);
+                for polygon in &repaired {
+                    self.add_valid_polygon(polygon)?;
+                }
+                Ok(report)
+            }
+        }
+    }
+
+    fn add_valid_polygon(
+        &mut self,
+        polygon: &impl geo_traits::PolygonTrait<T = Scalar>,
+    ) -> Result<(), crate::Error> {
+        if self.options.polygon_mode.fill() {
+            self.add_triangulation_input(Self::polygon_to_triangulation_input(polygon))?;
+        }
+        if !self.options.polygon_mode.outline() {
+            return Ok(());
+        }
         if let Some(exterior) = polygon.exterior() {
-            self.exterior.add_coords(exterior.coords())?;
+            self.exterior.add_ring(exterior.coords())?;
         }
         for interior in polygon.interiors() {
-            self.interiors.push(LineStringMeshBuilder::default());
-            self.interiors
-                .last_mut()
-                .unwrap()
-                .add_coords(interior.coords())?;
+            match self.options.outline_grouping {
+                OutlineGrouping::PerRing => {
+                    self.interiors.push(self.outline_builder());
+                    self.interiors
+                        .last_mut()
+                        .unwrap()
+                        .add_ring(interior.coords())?;
+                }
+                OutlineGrouping::Combined => self.exterior.add_ring(interior.coords())?,
+            }
         }
         Ok(())
     }
 
+    fn outline_builder(&self) -> LineStringMeshBuilder {
+        let builder = LineStringMeshBuilder::new().with_options(self.options.clone());
+        let mut builder = match &self.height_source {
+            Some(height_source) => builder.with_height_source(height_source.clone()),
+            None => builder,
+        };
+        if let Some(style) = &self.style {
+            builder.set_style(style);
+        }
+        builder
+    }
+
     pub fn add_polygon_from_exterior_coords(
         &mut self,
-        coords: impl Iterator<Item = impl CoordTrait<T = Scalar>> + Clone,
+        coords: impl Iterator<Item = impl CoordTrait<T = Scalar>>,
     ) -> Result<(), crate::Error> {
-        self.polygon
-            .add_earcutr_input(Self::exterior_coords_to_earcutr_input(coords.clone()));
-        self.exterior.add_coords(coords)?;
+        let mut vertices = Vec::with_capacity(coords.size_hint().0 * 2);
+        Self::flat_ring_coords(coords, &mut vertices);
+        debug_assert!(vertices.len() >= 6);
+
+        if self.options.polygon_mode.outline() {
+            self.exterior
+                .add_ring(vertices.chunks_exact(2).map(|xy| (xy[0], xy[1])))?;
+        }
+        if self.options.polygon_mode.fill() {
+            self.add_triangulation_input(TriangulationInput {
+                vertices,
+                interior_indices: vec![],
+            })?;
+        }
         Ok(())
     }
 
-    fn polygon_to_earcutr_input(
+    /// Like `add_polygon`, but returns where the polygon's triangles and outline end up in the
+    /// index buffers. Outlines must be combined for the outline range to cover the holes.
+    pub fn add_polygon_with_ranges(
+        &mut self,
         polygon: &impl geo_traits::PolygonTrait<T = Scalar>,
-    ) -> bevy_earcutr::EarcutrInput<Scalar> {
+    ) -> Result<PolygonIndexRanges, crate::Error> {
+        let (fill_start, outline_start) = (self.indices.len(), self.exterior.num_indices());
+        self.add_polygon(polygon)?;
+        Ok(PolygonIndexRanges {
+            fill: fill_start..self.indices.len(),
+            outline: outline_start..self.exterior.num_indices(),
+        })
+    }
+
+    /// Reserves room for the fill and outlines of the polygons, rects and triangles in
+    /// `geometry`, so that adding it allocates each buffer at most once.
+    pub fn reserve_for(&mut self, geometry: &impl GeometryTrait) {
+        self.reserve_counts(&Counts::of(geometry));
+    }
+
+    pub(crate) fn reserve_counts(&mut self, counts: &Counts) {
+        let num_coords = counts.exterior_coords + counts.interior_coords;
+        if self.options.polygon_mode.fill() {
+            self.vertices.reserve(num_coords);
+            // Earcut makes `n + 2h - 2` triangles of a polygon with `n` vertices and `h` holes.
+            self.indices
+                .reserve(3 * (num_coords + 2 * counts.interiors));
+        }
+        if self.options.polygon_mode.outline() {
+            match self.options.outline_grouping {
+                // Rect and triangle outlines get a closing coordinate.
+                OutlineGrouping::PerRing => {
+                    self.exterior
+                        .reserve(counts.exterior_coords + counts.polygons, counts.polygons);
+                    self.interiors.reserve(counts.interiors);
+                }
+                OutlineGrouping::Combined => self.exterior.reserve(
+                    num_coords + counts.polygons,
+                    counts.polygons + counts.interiors,
+                ),
+            }
+        }
+    }
+
+    /// Adds a triangle without going through the triangulator, unless validation is on. Its fill
+    /// is still refined, draped and patterned like other fills, and faces up (+z).
+    pub fn add_triangle(
+        &mut self,
+        triangle: &impl TriangleTrait<T = Scalar>,
+    ) -> Result<(), crate::Error> {
+        let ring = triangle.coords().map(|coord| (coord.x(), coord.y()));
+        let [(x0, y0), (x1, y1), (x2, y2)] = ring;
+        let clockwise = (x1 - x0) * (y2 - y0) < (y1 - y0) * (x2 - x0);
+        let triangle_indices = if clockwise {
+            vec![0, 2, 1]
+        } else {
+            vec![0, 1, 2]
+        };
+        self.add_convex_ring(&ring, triangle_indices)
+    }
+
+    /// Adds a rect as two triangles without going through the triangulator, unless validation is
+    /// on. Its fill is still refined, draped and patterned like other fills, and faces up (+z).
+    pub fn add_rect(&mut self, rect: &impl RectTrait<T = Scalar>) -> Result<(), crate::Error> {
+        let (min, max) = (rect.min(), rect.max());
+        let ring = [
+            (min.x(), min.y()),
+            (min.x(), max.y()),
+            (max.x(), max.y()),
+            (max.x(), min.y()),
+        ];
+        self.add_convex_ring(&ring, vec![0, 2, 1, 0, 3, 2])
+    }
+
+    /// Adds the fill of `ring` as `triangle_indices`, and its outline. With validation on, the
+    /// ring is added with `add_polygon` instead, so that degenerate rects and triangles are
+    /// caught.
+    fn add_convex_ring(
+        &mut self,
+        ring: &[(Scalar, Scalar)],
+        triangle_indices: Vec<usize>,
+    ) -> Result<(), crate::Error> {
+        if self.validation != Validation::Off {
+            let exterior = geo_types::LineString::from(ring.to_vec());
+            return self.add_polygon(&geo_types::Polygon::new(exterior, vec![]));
+        }
+        if self.options.polygon_mode.fill() {
+            self.add_triangulation(Triangulation {
+                vertices: ring.iter().flat_map(|(x, y)| [*x, *y]).collect(),
+                triangle_indices,
+            })?;
+        }
+        if self.options.polygon_mode.outline() {
+            self.exterior.add_ring(ring.iter().copied())?;
+        }
+        Ok(())
+    }
+
+    fn add_triangulation_input(
+        &mut self,
+        input: TriangulationInput<Scalar>,
+    ) -> Result<(), crate::Error> {
+        let triangulation = self.triangulator.triangulate(input)?;
+        self.add_triangulation(triangulation)
+    }
+
+    fn add_triangulation(
+        &mut self,
+        mut triangulation: Triangulation<Scalar>,
+    ) -> Result<(), crate::Error> {
+        if let Some(pattern) = self
+            .options
+            .fill_pattern
+            .filter(|pattern| !pattern.is_valid())
+        {
+            return Err(crate::Error::InvalidFillPattern(pattern));
+        }
+        let boundary = self
+            .options
+            .boundary_distances
+            .is_some()
+            .then(|| Boundary::new(&triangulation));
+        if let Some(refinement) = &self.refinement() {
+            triangulation = triangulation.refine(refinement)?;
+        }
+        let Triangulation {
+            vertices,
+            triangle_indices,
+        } = triangulation;
+        let vertices = vertices
+            .chunks_exact(2)
+            .map(|xy| {
+                let z = self.height_source.as_ref().and_then(|height_source| {
+                    height_source.height_at(xy[0].to_f64()?, xy[1].to_f64()?)
+                });
+                Ok([
+                    xy[0].to_f32().ok_or(crate::Error::CouldNotConvertToF32)?,
+                    xy[1].to_f32().ok_or(crate::Error::CouldNotConvertToF32)?,
+                    z.unwrap_or(0.0) + self.options.z_offset,
+                ])
+            })
+            .collect::<Result<Vec<Vertex>, crate::Error>>()?;
+        let (vertices, triangle_indices) = match &self.options.fill_pattern {
+            Some(pattern) => pattern.clip(&vertices, &triangle_indices),
+            None => (vertices, triangle_indices),
+        };
+        let distances = match &boundary {
+            Some(boundary) => vertices
+                .iter()
+                .map(|[x, y, _]| boundary.distance([f64::from(*x), f64::from(*y)]) as f32)
+                .collect(),
+            None => Vec::new(),
+        };
+        self.add_triangles(vertices, distances, triangle_indices);
+        Ok(())
+    }
+
+    /// `with_refinement`'s limits, with edges no longer than `MeshOptions::boundary_distances`.
+    fn refinement(&self) -> Option<Refinement> {
+        let max_edge_length = match self.options.boundary_distances {
+            Some(length) if length.is_finite() && length > 0. => length,
+            _ => return self.refinement,
+        };
+        let refinement = self.refinement.unwrap_or_default();
+        Some(Refinement {
+            max_edge_length: Some(
+                refinement
+                    .max_edge_length
+                    .map_or(max_edge_length, |length| length.min(max_edge_length)),
+            ),
+            ..refinement
+        })
+    }
+
+    /// Appends triangles to the current mesh, or to a new one if they don't fit. Triangulations
+    /// too large for any mesh are split between triangles, duplicating shared vertices.
+    /// `distances` are the vertices' boundary distances, or empty.
+    fn add_triangles(
+        &mut self,
+        vertices: Vec<Vertex>,
+        distances: Vec<f32>,
+        triangle_indices: Vec<usize>,
+    ) {
+        let max_vertices = self.options.max_vertices();
+        if self.chunk_len() + vertices.len() > max_vertices && self.chunk_len() > 0 {
+            self.chunks.start(self.vertices.len(), self.indices.len());
+        }
+
+        if vertices.len() <= max_vertices {
+            let index_base = self.chunk_len();
+            self.vertices.extend(vertices);
+            self.distances.extend(distances);
+            self.indices.extend(
+                triangle_indices
+                    .into_iter()
+                    .map(|index| (index_base + index) as u32),
+            );
+            return;
+        }
+
+        let mut chunk_indices = vec![None; vertices.len()];
+        for triangle in triangle_indices.chunks_exact(3) {
+            let new_vertices = triangle
+                .iter()
+                .filter(|index| chunk_indices[**index].is_none())
+                .count();
+            if self.chunk_len() + new_vertices > max_vertices {
+                self.chunks.start(self.vertices.len(), self.indices.len());
+                chunk_indices.fill(None);
+            }
+            for &index in triangle {
+                let chunk_index = match chunk_indices[index] {
+                    Some(chunk_index) => chunk_index,
+                    None => {
+                        let chunk_index = self.chunk_len() as u32;
+                        self.vertices.push(vertices[index]);
+                        self.distances.extend(distances.get(index));
+                        chunk_indices[index] = Some(chunk_index);
+                        chunk_index
+                    }
+                };
+                self.indices.push(chunk_index);
+            }
+        }
+    }
+
+    /// Number of fill vertices in the current mesh.
+    fn chunk_len(&self) -> usize {
+        self.vertices.len() - self.chunks.vertex_start()
+    }
+
+    /// Number of fill meshes the polygons are split into to stay within the index width.
+    pub fn num_meshes(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Like `PolygonMeshParts::try_from`, but returns several meshes instead of failing when the
+    /// fill or an outline exceeds the index width. The first holds the per-ring interior
+    /// outlines; an interior outline that was split takes several entries.
+    pub fn build_meshes(&self) -> Result<Vec<PolygonMeshParts>, crate::Error> {
+        if self.is_empty() {
+            return Err(crate::Error::EmptyGeometry);
+        }
+        let mut fill_meshes = Vec::new();
+        if self.options.polygon_mode.fill() {
+            for (vertices, indices) in self.chunks.ranges(self.vertices.len(), self.indices.len()) {
+                fill_meshes.push(styled_fill_mesh(
+                    self.vertices[vertices.clone()].to_vec(),
+                    self.indices[indices].to_vec(),
+                    self.distances
+                        .get(vertices.clone())
+                        .unwrap_or_default()
+                        .to_vec(),
+                    self.styles.vertex_styles(vertices),
+                    &self.options,
+                )?);
+            }
+        }
+        let outline_meshes = if self.options.polygon_mode.outline() {
+            self.exterior.build_meshes()?
+        } else {
+            Vec::new()
+        };
+        let mut interior_meshes = Vec::new();
+        for interior in &self.interiors {
+            interior_meshes.extend(interior.build_meshes()?);
+        }
+
+        let num_meshes = fill_meshes.len().max(outline_meshes.len());
+        let (mut fill_meshes, mut outline_meshes) =
+            (fill_meshes.into_iter(), outline_meshes.into_iter());
+        Ok((0..num_meshes)
+            .map(|_| {
+                polygon_mesh(
+                    fill_meshes.next(),
+                    outline_meshes.next(),
+                    std::mem::take(&mut interior_meshes),
+                    &self.options,
+                )
+            })
+            .collect())
+    }
+
+    fn polygon_to_triangulation_input(
+        polygon: &impl geo_traits::PolygonTrait<T = Scalar>,
+    ) -> TriangulationInput<Scalar> {
         let mut vertices = Vec::with_capacity(polygon_coords_count(polygon) * 2);
         let mut interior_indices = Vec::with_capacity(polygon.num_interiors());
         debug_assert!(
             polygon
                 .exterior()
                 .map_or(0, |exterior| exterior.num_coords())
-                >= 4
+                >= 3
         );
 
         if let Some(exterior) = polygon.exterior() {
-            Self::flat_line_string_coords_2(exterior.coords(), &mut vertices);
+            Self::flat_ring_coords(exterior.coords(), &mut vertices);
         }
 
         for interior in polygon.interiors() {
-            debug_assert!(interior.num_coords() >= 4);
+            debug_assert!(interior.num_coords() >= 3);
             interior_indices.push(vertices.len() / 2);
-            Self::flat_line_string_coords_2(interior.coords(), &mut vertices);
+            Self::flat_ring_coords(interior.coords(), &mut vertices);
         }
 
-        bevy_earcutr::EarcutrInput {
+        TriangulationInput {
             vertices,
             interior_indices,
         }
     }
 
-    fn exterior_coords_to_earcutr_input(
-        exterior: impl Iterator<Item = impl CoordTrait<T = Scalar>> + Clone,
-    ) -> bevy_earcutr::EarcutrInput<Scalar> {
-        let count = exterior.clone().count();
-        let mut vertices = Vec::with_capacity(count * 2);
-        debug_assert!(count >= 4);
-
-        Self::flat_line_string_coords_2(exterior, &mut vertices);
-
-        bevy_earcutr::EarcutrInput {
-            vertices,
-            interior_indices: vec![],
-        }
-    }
-
-    fn flat_line_string_coords_2(
-        line_string_coords: impl Iterator<Item = impl CoordTrait<T = Scalar>>,
+    /// Appends the ring's coordinates, leaving out the first one's repeat at the end.
+    fn flat_ring_coords(
+        ring_coords: impl Iterator<Item = impl CoordTrait<T = Scalar>>,
         vertices: &mut Vec<Scalar>,
     ) {
-        for coord in line_string_coords {
+        let start = vertices.len();
+        for coord in ring_coords {
             vertices.push(coord.x());
             vertices.push(coord.y());
         }
+        if vertices.len() - start > 2
+            && vertices[start..start + 2] == vertices[vertices.len() - 2..]
+        {
+            vertices.truncate(vertices.len() - 2);
+        }
     }
 }
 
@@ -122,32 +688,193 @@ fn polygon_coords_count<P: PolygonTrait>(polygon: &P) -> usize {
             .sum::<usize>()
 }
 
-impl<Scalar: geo_types::CoordFloat> TryFrom<PolygonMeshBuilder<Scalar>> for PolygonMesh {
+impl<Scalar: geo_types::CoordFloat> TryFrom<PolygonMeshBuilder<Scalar>> for PolygonMeshParts {
     type Error = crate::Error;
 
     fn try_from(polygon_mesh_builder: PolygonMeshBuilder<Scalar>) -> Result<Self, Self::Error> {
-        polygon_mesh_builder
-            .polygon
-            .build()
-            .map_err(crate::Error::BevyEarcutr)
-            .and_then(|polygon_mesh| {
-                let exterior_mesh = Mesh::try_from(polygon_mesh_builder.exterior)?;
-                let interior_meshes = polygon_mesh_builder
-                    .interiors
-                    .into_iter()
-                    .map(Mesh::try_from)
-                    .collect::<Result<Vec<_>, _>>()?;
-                Ok(PolygonMesh {
-                    mesh: polygon_mesh,
-                    exterior_mesh,
-                    interior_meshes,
-                })
+        if polygon_mesh_builder.num_meshes() > 1 {
+            return Err(crate::Error::IndexOverflow);
+        }
+        let options = polygon_mesh_builder.options;
+        let outline_mesh = options
+            .polygon_mode
+            .outline()
+            .then(|| Mesh::try_from(polygon_mesh_builder.exterior))
+            .transpose()?;
+        let interior_meshes = polygon_mesh_builder
+            .interiors
+            .into_iter()
+            .map(Mesh::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mesh = options
+            .polygon_mode
+            .fill()
+            .then(|| {
+                let styles = polygon_mesh_builder
+                    .styles
+                    .vertex_styles(0..polygon_mesh_builder.vertices.len());
+                styled_fill_mesh(
+                    polygon_mesh_builder.vertices,
+                    polygon_mesh_builder.indices,
+                    polygon_mesh_builder.distances,
+                    styles,
+                    &options,
+                )
+            })
+            .transpose()?;
+
+        Ok(polygon_mesh(mesh, outline_mesh, interior_meshes, &options))
+    }
+}
+
+/// Builds meshes from a copy of the buffers, leaving the builder ready for reuse.
+impl<Scalar: geo_types::CoordFloat> TryFrom<&PolygonMeshBuilder<Scalar>> for PolygonMeshParts {
+    type Error = crate::Error;
+
+    fn try_from(polygon_mesh_builder: &PolygonMeshBuilder<Scalar>) -> Result<Self, Self::Error> {
+        if polygon_mesh_builder.num_meshes() > 1 {
+            return Err(crate::Error::IndexOverflow);
+        }
+        let options = &polygon_mesh_builder.options;
+        let outline_mesh = options
+            .polygon_mode
+            .outline()
+            .then(|| Mesh::try_from(&polygon_mesh_builder.exterior))
+            .transpose()?;
+        let interior_meshes = polygon_mesh_builder
+            .interiors
+            .iter()
+            .map(Mesh::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mesh = options
+            .polygon_mode
+            .fill()
+            .then(|| {
+                styled_fill_mesh(
+                    polygon_mesh_builder.vertices.clone(),
+                    polygon_mesh_builder.indices.clone(),
+                    polygon_mesh_builder.distances.clone(),
+                    polygon_mesh_builder
+                        .styles
+                        .vertex_styles(0..polygon_mesh_builder.vertices.len()),
+                    options,
+                )
             })
+            .transpose()?;
+
+        Ok(polygon_mesh(mesh, outline_mesh, interior_meshes, options))
     }
 }
 
+impl<Scalar: geo_types::CoordFloat> TryFrom<PolygonMeshBuilder<Scalar>> for PolygonMesh {
+    type Error = crate::Error;
+
+    fn try_from(polygon_mesh_builder: PolygonMeshBuilder<Scalar>) -> Result<Self, Self::Error> {
+        PolygonMeshParts::try_from(polygon_mesh_builder)?.try_into()
+    }
+}
+
+/// Builds meshes from a copy of the buffers, leaving the builder ready for reuse.
+impl<Scalar: geo_types::CoordFloat> TryFrom<&PolygonMeshBuilder<Scalar>> for PolygonMesh {
+    type Error = crate::Error;
+
+    fn try_from(polygon_mesh_builder: &PolygonMeshBuilder<Scalar>) -> Result<Self, Self::Error> {
+        PolygonMeshParts::try_from(polygon_mesh_builder)?.try_into()
+    }
+}
+
+fn polygon_mesh(
+    mesh: Option<Mesh>,
+    outline_mesh: Option<Mesh>,
+    interior_meshes: Vec<Mesh>,
+    options: &MeshOptions,
+) -> PolygonMeshParts {
+    match options.outline_grouping {
+        OutlineGrouping::PerRing => PolygonMeshParts {
+            mesh,
+            exterior_mesh: outline_mesh,
+            interior_meshes,
+            outline_mesh: None,
+        },
+        OutlineGrouping::Combined => PolygonMeshParts {
+            mesh,
+            exterior_mesh: None,
+            interior_meshes,
+            outline_mesh,
+        },
+    }
+}
+
+/// A fill mesh raised by each vertex's z order and colored with its fill color, with boundary
+/// distances unless `distances` is empty.
+fn styled_fill_mesh(
+    mut vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    distances: Vec<f32>,
+    styles: Option<Vec<FeatureStyle>>,
+    options: &MeshOptions,
+) -> Result<Mesh, crate::Error> {
+    if let Some(styles) = &styles {
+        apply_z_order(&mut vertices, styles);
+    }
+    let mut mesh = fill_mesh(vertices, indices, options)?;
+    if let Some(styles) = styles {
+        let colors = styles
+            .iter()
+            .map(|style| style.fill_color.to_f32_array())
+            .collect::<Vec<_>>();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    }
+    if !distances.is_empty() {
+        mesh.insert_attribute(ATTRIBUTE_BOUNDARY_DISTANCE, distances);
+    }
+    Ok(mesh)
+}
+
+pub(crate) fn fill_mesh(
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    options: &MeshOptions,
+) -> Result<Mesh, crate::Error> {
+    if vertices.is_empty() {
+        return Err(crate::Error::EmptyGeometry);
+    }
+    let mut mesh = Mesh::new(
+        bevy::render::render_resource::PrimitiveTopology::TriangleList,
+        options.asset_usages,
+    );
+    if options.normals {
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_NORMAL,
+            crate::terrain::vertex_normals(&vertices, &indices),
+        );
+    }
+    if options.uvs {
+        let uvs = vertices
+            .iter()
+            .map(|[x, y, _]| [*x, *y])
+            .collect::<Vec<_>>();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    }
+    mesh.insert_indices(options.indices(indices, vertices.len())?);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
+    Ok(mesh)
+}
+
 impl<Scalar: geo_types::CoordFloat> crate::build_mesh::BuildMesh for PolygonMeshBuilder<Scalar> {
     fn build(self) -> Result<crate::GeometryMesh, crate::Error> {
-        Ok(crate::GeometryMesh::Polygon(PolygonMesh::try_from(self)?))
+        Ok(PolygonMeshParts::try_from(self)?.into())
+    }
+
+    fn build_ref(&self) -> Result<crate::GeometryMesh, crate::Error> {
+        Ok(PolygonMeshParts::try_from(self)?.into())
+    }
+
+    fn build_meshes(&self) -> Result<Vec<crate::GeometryMesh>, crate::Error> {
+        Ok(self
+            .build_meshes()?
+            .into_iter()
+            .map(crate::GeometryMesh::from)
+            .collect())
     }
 }
@@ -0,0 +1,95 @@
+#import bevy_pbr::mesh_functions::{get_world_from_local, mesh_position_local_to_clip}
+#import bevy_pbr::mesh_view_bindings::view
+
+struct ScreenSpaceLine {
+    color: vec4<f32>,
+    width: f32,
+    // On and off lengths in line widths, padded with zeros.
+    dash_lengths: array<vec4<f32>, 2>,
+    dash_period: f32,
+    dash_offset: f32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> line: ScreenSpaceLine;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) extrusion: vec2<f32>,
+#ifdef VERTEX_STYLES
+    @location(2) color: vec4<f32>,
+    @location(3) width: f32,
+#endif
+#ifdef DASHED
+    @location(4) line_distance: f32,
+#endif
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    // Interpolates from 1 on the outer edges to 0 along the line.
+    @location(0) extrusion: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) width: f32,
+    @location(3) line_distance: f32,
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+#ifdef VERTEX_STYLES
+    out.color = vertex.color;
+    out.width = vertex.width;
+#else
+    out.color = line.color;
+    out.width = line.width;
+#endif
+#ifdef DASHED
+    out.line_distance = vertex.line_distance;
+#endif
+
+    let world_from_local = get_world_from_local(vertex.instance_index);
+    var clip = mesh_position_local_to_clip(world_from_local, vec4<f32>(vertex.position, 1.0));
+    let extruded = mesh_position_local_to_clip(
+        world_from_local,
+        vec4<f32>(vertex.position + vec3<f32>(vertex.extrusion, 0.0), 1.0),
+    );
+
+    // Pixels per unit of normalized device coordinates.
+    let half_viewport = view.viewport.zw * 0.5;
+    let direction = extruded.xy / extruded.w - clip.xy / clip.w;
+    let direction_pixels = direction * half_viewport;
+    if length(vertex.extrusion) > 0.0 && length(direction_pixels) > 0.0 {
+        // One more pixel than the half width, to fade out over.
+        let offset = normalize(direction_pixels) * (out.width * 0.5 + 1.0);
+        clip = vec4<f32>(clip.xy + offset / half_viewport * clip.w, clip.zw);
+    }
+
+    out.clip_position = clip;
+    out.extrusion = vertex.extrusion;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+#ifdef DASHED
+    // Geo units per pixel along the line, the same across it.
+    let scale = length(vec2<f32>(dpdx(in.line_distance), dpdy(in.line_distance)));
+    let widths = in.line_distance / max(scale, 1e-6) / max(in.width, 1e-6) + line.dash_offset;
+    var along = widths - floor(widths / line.dash_period) * line.dash_period;
+    for (var index = 0u; index < 8u; index++) {
+        let dash = line.dash_lengths[index / 4u][index % 4u];
+        if along < dash {
+            if index % 2u == 1u {
+                discard;
+            }
+            break;
+        }
+        along -= dash;
+    }
+#endif
+
+    // Pixels from the outer edge, including the anti-aliasing pixel.
+    let inside = (1.0 - length(in.extrusion)) * (in.width * 0.5 + 1.0);
+    return vec4<f32>(in.color.rgb, in.color.a * clamp(inside, 0.0, 1.0));
+}
@@ -0,0 +1,404 @@
+use bevy::color::{LinearRgba, Mix};
+use bevy::mesh::{MeshVertexAttribute, VertexFormat};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::BuildHasher;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Width a `Style` gives each line string and outline vertex. Added to styled line meshes along
+/// with `Mesh::ATTRIBUTE_COLOR`, for materials that draw lines of varying width.
+pub const ATTRIBUTE_LINE_WIDTH: MeshVertexAttribute = MeshVertexAttribute::new(
+    "Vertex_LineWidth",
+    0x6765_6f62_6576_7904,
+    VertexFormat::Float32,
+);
+
+/// A feature property, as read from GeoJSON properties or Shapefile attributes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+impl Value {
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Number(value as f64)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+/// The properties of a feature, looked up by name.
+pub trait Properties {
+    fn property(&self, name: &str) -> Option<Value>;
+}
+
+impl<S: BuildHasher> Properties for HashMap<String, Value, S> {
+    fn property(&self, name: &str) -> Option<Value> {
+        self.get(name).cloned()
+    }
+}
+
+impl Properties for BTreeMap<String, Value> {
+    fn property(&self, name: &str) -> Option<Value> {
+        self.get(name).cloned()
+    }
+}
+
+/// Values that `Expression::Interpolated` can blend between.
+pub trait Interpolate: Clone {
+    /// `self` at `t` = 0, `other` at `t` = 1.
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for LinearRgba {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.mix(other, t)
+    }
+}
+
+type PropertyFunction<T> = Arc<dyn Fn(&dyn Properties) -> Option<T> + Send + Sync>;
+
+/// How a style value is computed from a feature's properties. Evaluates to `None` when the
+/// property is missing or doesn't fit, and the `Style` default is used instead.
+#[derive(Clone)]
+pub enum Expression<T> {
+    Constant(T),
+    /// The value paired with the property's value.
+    Categorical {
+        property: String,
+        categories: Vec<(Value, T)>,
+    },
+    /// The value of the last step whose threshold the numeric property reaches, or `base`
+    /// below the first. Steps are in increasing order of threshold.
+    Stepped {
+        property: String,
+        base: T,
+        steps: Vec<(f64, T)>,
+    },
+    /// Linearly interpolated between the stops either side of the numeric property, and
+    /// clamped to the first and last. Stops are in increasing order of input.
+    Interpolated {
+        property: String,
+        stops: Vec<(f64, T)>,
+    },
+    Function(PropertyFunction<T>),
+}
+
+impl<T> Expression<T> {
+    pub fn categorical(
+        property: impl Into<String>,
+        categories: impl IntoIterator<Item = (impl Into<Value>, T)>,
+    ) -> Self {
+        Expression::Categorical {
+            property: property.into(),
+            categories: categories
+                .into_iter()
+                .map(|(value, output)| (value.into(), output))
+                .collect(),
+        }
+    }
+
+    pub fn stepped(
+        property: impl Into<String>,
+        base: T,
+        steps: impl IntoIterator<Item = (f64, T)>,
+    ) -> Self {
+        Expression::Stepped {
+            property: property.into(),
+            base,
+            steps: steps.into_iter().collect(),
+        }
+    }
+
+    pub fn interpolated(
+        property: impl Into<String>,
+        stops: impl IntoIterator<Item = (f64, T)>,
+    ) -> Self {
+        Expression::Interpolated {
+            property: property.into(),
+            stops: stops.into_iter().collect(),
+        }
+    }
+
+    pub fn function(
+        function: impl Fn(&dyn Properties) -> Option<T> + Send + Sync + 'static,
+    ) -> Self {
+        Expression::Function(Arc::new(function))
+    }
+}
+
+impl<T: Interpolate> Expression<T> {
+    pub fn evaluate(&self, properties: &dyn Properties) -> Option<T> {
+        match self {
+            Expression::Constant(value) => Some(value.clone()),
+            Expression::Categorical {
+                property,
+                categories,
+            } => {
+                let value = properties.property(property)?;
+                categories
+                    .iter()
+                    .find(|(category, _)| *category == value)
+                    .map(|(_, output)| output.clone())
+            }
+            Expression::Stepped {
+                property,
+                base,
+                steps,
+            } => {
+                let input = properties.property(property)?.as_number()?;
+                let reached = steps.partition_point(|(threshold, _)| *threshold <= input);
+                Some(match reached {
+                    0 => base.clone(),
+                    reached => steps[reached - 1].1.clone(),
+                })
+            }
+            Expression::Interpolated { property, stops } => {
+                let input = properties.property(property)?.as_number()?;
+                let after = stops.partition_point(|(stop, _)| *stop <= input);
+                match (stops.get(after.wrapping_sub(1)), stops.get(after)) {
+                    (Some((from, low)), Some((to, high))) => {
+                        Some(low.interpolate(high, ((input - from) / (to - from)) as f32))
+                    }
+                    (Some((_, value)), None) | (None, Some((_, value))) => Some(value.clone()),
+                    (None, None) => None,
+                }
+            }
+            Expression::Function(function) => function(properties),
+        }
+    }
+}
+
+impl<T> From<T> for Expression<T> {
+    fn from(value: T) -> Self {
+        Expression::Constant(value)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Expression<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Constant(value) => f.debug_tuple("Constant").field(value).finish(),
+            Expression::Categorical {
+                property,
+                categories,
+            } => f
+                .debug_struct("Categorical")
+                .field("property", property)
+                .field("categories", categories)
+                .finish(),
+            Expression::Stepped {
+                property,
+                base,
+                steps,
+            } => f
+                .debug_struct("Stepped")
+                .field("property", property)
+                .field("base", base)
+                .field("steps", steps)
+                .finish(),
+            Expression::Interpolated { property, stops } => f
+                .debug_struct("Interpolated")
+                .field("property", property)
+                .field("stops", stops)
+                .finish(),
+            Expression::Function(_) => f.write_str("Function"),
+        }
+    }
+}
+
+/// Maps feature properties to how their meshes look.
+#[derive(Clone, Debug)]
+pub struct Style {
+    pub fill_color: Expression<LinearRgba>,
+    pub stroke_color: Expression<LinearRgba>,
+    pub stroke_width: Expression<f32>,
+    pub point_size: Expression<f32>,
+    pub z_order: Expression<f32>,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        let defaults = FeatureStyle::default();
+        Style {
+            fill_color: defaults.fill_color.into(),
+            stroke_color: defaults.stroke_color.into(),
+            stroke_width: defaults.stroke_width.into(),
+            point_size: defaults.point_size.into(),
+            z_order: defaults.z_order.into(),
+        }
+    }
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_fill_color(mut self, fill_color: impl Into<Expression<LinearRgba>>) -> Self {
+        self.fill_color = fill_color.into();
+        self
+    }
+
+    pub fn with_stroke_color(mut self, stroke_color: impl Into<Expression<LinearRgba>>) -> Self {
+        self.stroke_color = stroke_color.into();
+        self
+    }
+
+    pub fn with_stroke_width(mut self, stroke_width: impl Into<Expression<f32>>) -> Self {
+        self.stroke_width = stroke_width.into();
+        self
+    }
+
+    pub fn with_point_size(mut self, point_size: impl Into<Expression<f32>>) -> Self {
+        self.point_size = point_size.into();
+        self
+    }
+
+    pub fn with_z_order(mut self, z_order: impl Into<Expression<f32>>) -> Self {
+        self.z_order = z_order.into();
+        self
+    }
+
+    /// Evaluates every expression against a feature's properties.
+    pub fn resolve(&self, properties: &dyn Properties) -> FeatureStyle {
+        let defaults = FeatureStyle::default();
+        FeatureStyle {
+            fill_color: self
+                .fill_color
+                .evaluate(properties)
+                .unwrap_or(defaults.fill_color),
+            stroke_color: self
+                .stroke_color
+                .evaluate(properties)
+                .unwrap_or(defaults.stroke_color),
+            stroke_width: self
+                .stroke_width
+                .evaluate(properties)
+                .unwrap_or(defaults.stroke_width),
+            point_size: self
+                .point_size
+                .evaluate(properties)
+                .unwrap_or(defaults.point_size),
+            z_order: self
+                .z_order
+                .evaluate(properties)
+                .unwrap_or(defaults.z_order),
+        }
+    }
+}
+
+/// A `Style` resolved for one feature, as builders write it onto its vertices: fills get
+/// `fill_color`, line strings and outlines `stroke_color` and `stroke_width`, and points
+/// `fill_color` and `point_size`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeatureStyle {
+    pub fill_color: LinearRgba,
+    pub stroke_color: LinearRgba,
+    pub stroke_width: f32,
+    pub point_size: f32,
+    /// Added to the z of the feature's vertices, so that higher values are drawn over lower
+    /// ones when seen from above.
+    pub z_order: f32,
+}
+
+impl Default for FeatureStyle {
+    fn default() -> Self {
+        FeatureStyle {
+            fill_color: LinearRgba::WHITE,
+            stroke_color: LinearRgba::BLACK,
+            stroke_width: 1.,
+            point_size: 1.,
+            z_order: 0.,
+        }
+    }
+}
+
+/// The style of each run of a builder's vertices, by the index of the run's first vertex.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct StyleSpans {
+    spans: Vec<(usize, FeatureStyle)>,
+}
+
+impl StyleSpans {
+    /// Styles vertices from `vertex` on with `style`.
+    pub(crate) fn start(&mut self, vertex: usize, style: FeatureStyle) {
+        match self.spans.last_mut() {
+            Some((start, last)) if *start == vertex => *last = style,
+            _ => self.spans.push((vertex, style)),
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.spans.clear();
+    }
+
+    /// The style of each vertex in `vertices`, or `None` if no style was ever set. Vertices
+    /// added before the first style get the default one.
+    pub(crate) fn vertex_styles(&self, vertices: Range<usize>) -> Option<Vec<FeatureStyle>> {
+        if self.spans.is_empty() {
+            return None;
+        }
+        let mut span = self
+            .spans
+            .partition_point(|(start, _)| *start <= vertices.start);
+        Some(
+            vertices
+                .map(|vertex| {
+                    while self
+                        .spans
+                        .get(span)
+                        .is_some_and(|(start, _)| *start <= vertex)
+                    {
+                        span += 1;
+                    }
+                    match span {
+                        0 => FeatureStyle::default(),
+                        span => self.spans[span - 1].1,
+                    }
+                })
+                .collect(),
+        )
+    }
+}
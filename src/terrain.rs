@@ -0,0 +1,167 @@
+use crate::height::HeightmapGrid;
+use crate::options::MeshOptions;
+use bevy::prelude::Mesh;
+
+/// Builds a terrain mesh with a vertex at each pixel centre of `heightmap`.
+///
+/// Like the vector meshes, positions are the geo coordinates with the elevation as z, and cells
+/// are split along the same diagonal `HeightSource` interpolates across, so vector layers draped
+/// onto `heightmap` lie exactly on the terrain. UVs map the raster's extent to `0.0..=1.0` with
+/// `v` growing along rows. Cells with a non-finite corner are left out.
+pub fn terrain_to_mesh(heightmap: &HeightmapGrid) -> Result<Mesh, crate::Error> {
+    let mut meshes = terrain_to_mesh_with_options(heightmap, MeshOptions::default())?;
+    match meshes.len() {
+        1 => Ok(meshes.remove(0)),
+        _ => Err(crate::Error::IndexOverflow),
+    }
+}
+
+/// Like `terrain_to_mesh`, but with `MeshOptions::index_width` and `MeshOptions::asset_usages`
+/// from `options`; its other fields don't apply to terrain. Where one mesh would exceed the
+/// index width, the terrain is split between triangles into several, duplicating the vertices
+/// they share.
+pub fn terrain_to_mesh_with_options(
+    heightmap: &HeightmapGrid,
+    options: MeshOptions,
+) -> Result<Vec<Mesh>, crate::Error> {
+    let (width, height) = (heightmap.width(), heightmap.height());
+    if width < 2 || height < 2 {
+        return Err(crate::Error::EmptyGeometry);
+    }
+
+    let mut positions = Vec::with_capacity(width * height);
+    let mut uvs = Vec::with_capacity(width * height);
+    for row in 0..height {
+        for column in 0..width {
+            let [x, y] = heightmap
+                .transform()
+                .pixel_to_geo(column as f64 + 0.5, row as f64 + 0.5);
+            let z = heightmap.value(column, row).unwrap_or(f32::NAN);
+            positions.push([x as f32, y as f32, z]);
+            uvs.push([
+                (column as f32 + 0.5) / width as f32,
+                (row as f32 + 0.5) / height as f32,
+            ]);
+        }
+    }
+
+    // Keep triangles counter-clockwise in geo space, whichever way rows and columns run.
+    let t = heightmap.transform().0;
+    let flipped = t[1] * t[5] - t[2] * t[4] < 0.;
+
+    let mut indices = Vec::with_capacity((width - 1) * (height - 1) * 6);
+    for row in 0..height - 1 {
+        for column in 0..width - 1 {
+            let top_left = row * width + column;
+            let [top_right, bottom_left, bottom_right] =
+                [top_left + 1, top_left + width, top_left + width + 1];
+            if [top_left, top_right, bottom_left, bottom_right]
+                .iter()
+                .any(|&i| !positions[i][2].is_finite())
+            {
+                continue;
+            }
+            let triangles = if flipped {
+                [
+                    top_left,
+                    bottom_right,
+                    top_right,
+                    top_left,
+                    bottom_left,
+                    bottom_right,
+                ]
+            } else {
+                [
+                    top_left,
+                    top_right,
+                    bottom_right,
+                    top_left,
+                    bottom_right,
+                    bottom_left,
+                ]
+            };
+            indices.extend(triangles.map(|i| i as u32));
+        }
+    }
+
+    // Over the whole terrain, so that split meshes shade the same along their seams.
+    let normals = vertex_normals(&positions, &indices);
+
+    split_triangles(&indices, positions.len(), options.max_vertices())
+        .into_iter()
+        .map(|(vertices, indices)| {
+            let mut mesh = Mesh::new(
+                bevy::render::render_resource::PrimitiveTopology::TriangleList,
+                options.asset_usages,
+            );
+            mesh.insert_indices(options.indices(indices, vertices.len())?);
+            let copy = |values: &[[f32; 3]]| -> Vec<[f32; 3]> {
+                vertices.iter().map(|&i| values[i]).collect()
+            };
+            mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, copy(&positions));
+            mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, copy(&normals));
+            mesh.insert_attribute(
+                Mesh::ATTRIBUTE_UV_0,
+                vertices.iter().map(|&i| uvs[i]).collect::<Vec<_>>(),
+            );
+            Ok(mesh)
+        })
+        .collect()
+}
+
+/// Splits triangles, in order, between meshes of at most `max_vertices` vertices. Each mesh is
+/// the vertices it uses and its triangles' indices into them.
+fn split_triangles(
+    indices: &[u32],
+    num_vertices: usize,
+    max_vertices: usize,
+) -> Vec<(Vec<usize>, Vec<u32>)> {
+    if num_vertices <= max_vertices {
+        return vec![((0..num_vertices).collect(), indices.to_vec())];
+    }
+
+    let mut meshes = Vec::new();
+    let (mut vertices, mut mesh_indices) = (Vec::new(), Vec::new());
+    let mut new_indices = vec![None; num_vertices];
+    for triangle in indices.chunks_exact(3) {
+        let new_vertices = triangle
+            .iter()
+            .filter(|index| new_indices[**index as usize].is_none())
+            .count();
+        if vertices.len() + new_vertices > max_vertices {
+            for &vertex in &vertices {
+                new_indices[vertex] = None;
+            }
+            meshes.push((
+                std::mem::take(&mut vertices),
+                std::mem::take(&mut mesh_indices),
+            ));
+        }
+        for &index in triangle {
+            let new_index = *new_indices[index as usize].get_or_insert_with(|| {
+                vertices.push(index as usize);
+                (vertices.len() - 1) as u32
+            });
+            mesh_indices.push(new_index);
+        }
+    }
+    meshes.push((vertices, mesh_indices));
+    meshes
+}
+
+/// Area-weighted average of the upward-facing normals of the triangles around each vertex.
+pub(crate) fn vertex_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut normals = vec![bevy::math::Vec3::ZERO; positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| bevy::math::Vec3::from(positions[triangle[i] as usize]));
+        let normal = (b - a).cross(c - a);
+        let normal = if normal.z < 0. { -normal } else { normal };
+        for &i in triangle {
+            normals[i as usize] += normal;
+        }
+    }
+    normals
+        .into_iter()
+        .map(|normal| normal.try_normalize().unwrap_or(bevy::math::Vec3::Z).into())
+        .collect()
+}
@@ -0,0 +1,92 @@
+use crate::build_mesh::{BuildBevyMeshesContext, BuildMesh};
+use crate::GeometryMesh;
+use geo_traits::{GeometryCollectionTrait, GeometryTrait, GeometryType};
+
+/// Meshes of a geometry that keep the nesting of its geometry collections, so that members can
+/// be styled separately.
+pub enum GeometryMeshTree {
+    /// A geometry's meshes, as from `BuildMesh::build_meshes`: one per kind of mesh it needs,
+    /// split where a mesh would overflow `MeshOptions::index_width`, and none if it is empty.
+    Geometry {
+        path: Vec<usize>,
+        meshes: Vec<GeometryMesh>,
+    },
+    Collection {
+        path: Vec<usize>,
+        members: Vec<GeometryMeshTree>,
+    },
+}
+
+impl GeometryMeshTree {
+    /// Index of the node within each enclosing collection, outermost first. Empty for the root.
+    pub fn path(&self) -> &[usize] {
+        match self {
+            GeometryMeshTree::Geometry { path, .. } | GeometryMeshTree::Collection { path, .. } => {
+                path
+            }
+        }
+    }
+
+    /// The node at `path`, relative to this one.
+    pub fn get(&self, path: &[usize]) -> Option<&GeometryMeshTree> {
+        match (path, self) {
+            ([], _) => Some(self),
+            ([index, rest @ ..], GeometryMeshTree::Collection { members, .. }) => {
+                members.get(*index)?.get(rest)
+            }
+            _ => None,
+        }
+    }
+
+    /// The meshes of every geometry in the tree with their paths, depth first.
+    pub fn meshes(&self) -> Vec<(&[usize], &GeometryMesh)> {
+        let mut meshes = Vec::new();
+        self.collect_meshes(&mut meshes);
+        meshes
+    }
+
+    fn collect_meshes<'a>(&'a self, meshes: &mut Vec<(&'a [usize], &'a GeometryMesh)>) {
+        match self {
+            GeometryMeshTree::Geometry {
+                path,
+                meshes: geometry_meshes,
+            } => meshes.extend(geometry_meshes.iter().map(|mesh| (path.as_slice(), mesh))),
+            GeometryMeshTree::Collection { members, .. } => {
+                for member in members {
+                    member.collect_meshes(meshes);
+                }
+            }
+        }
+    }
+}
+
+/// Builds each member with `ctx`, clearing it in between.
+pub(crate) fn build_tree<Scalar: geo_types::CoordFloat>(
+    geometry: &impl GeometryTrait<T = Scalar>,
+    ctx: &mut BuildBevyMeshesContext<Scalar>,
+    path: &mut Vec<usize>,
+) -> Result<GeometryMeshTree, crate::Error> {
+    if let GeometryType::GeometryCollection(collection) = geometry.as_type() {
+        let mut members = Vec::with_capacity(collection.num_geometries());
+        for (index, member) in collection.geometries().enumerate() {
+            path.push(index);
+            let member = build_tree(&member, ctx, path);
+            path.pop();
+            members.push(member?);
+        }
+        return Ok(GeometryMeshTree::Collection {
+            path: path.clone(),
+            members,
+        });
+    }
+
+    ctx.clear();
+    ctx.add_geometry(geometry)?;
+    Ok(GeometryMeshTree::Geometry {
+        path: path.clone(),
+        meshes: match ctx.is_empty() {
+            true => Vec::new(),
+            false => ctx.build_meshes()?,
+        },
+    })
+}
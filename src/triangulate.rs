@@ -0,0 +1,70 @@
+use geo_types::CoordFloat;
+
+#[cfg(feature = "spade")]
+mod delaunay;
+#[cfg(feature = "lyon")]
+mod lyon;
+mod refine;
+
+#[cfg(feature = "spade")]
+pub use delaunay::ConstrainedDelaunayTriangulator;
+#[cfg(feature = "lyon")]
+pub use lyon::LyonTriangulator;
+pub use refine::Refinement;
+
+/// A polygon to triangulate, in the layout earcutr expects. Rings are open: their first
+/// coordinate is not repeated at the end.
+pub struct TriangulationInput<Scalar: CoordFloat> {
+    /// Flat `[x0, y0, x1, y1, ...]` coordinates of the exterior followed by each interior.
+    pub vertices: Vec<Scalar>,
+    /// Index into `vertices` (in coordinates, not scalars) at which each interior starts.
+    pub interior_indices: Vec<usize>,
+}
+
+impl<Scalar: CoordFloat> TriangulationInput<Scalar> {
+    /// Iterates the rings as `[x, y]` slices, exterior first.
+    pub fn rings(&self) -> impl Iterator<Item = &[Scalar]> {
+        let starts = std::iter::once(0).chain(self.interior_indices.iter().copied());
+        let ends = self
+            .interior_indices
+            .iter()
+            .copied()
+            .chain(std::iter::once(self.vertices.len() / 2));
+        starts
+            .zip(ends)
+            .map(|(start, end)| &self.vertices[start * 2..end * 2])
+    }
+}
+
+pub struct Triangulation<Scalar: CoordFloat> {
+    /// Flat `[x0, y0, x1, y1, ...]` coordinates. Backends may add vertices to the input's.
+    pub vertices: Vec<Scalar>,
+    /// Three indices into `vertices` (in coordinates) per triangle.
+    pub triangle_indices: Vec<usize>,
+}
+
+/// Turns polygons into triangles for the fill mesh of `PolygonMeshBuilder`.
+pub trait Triangulator<Scalar: CoordFloat>: Send + Sync {
+    fn triangulate(
+        &self,
+        input: TriangulationInput<Scalar>,
+    ) -> Result<Triangulation<Scalar>, crate::Error>;
+}
+
+/// The default backend. Fast, but prone to slivers and to failing on degenerate input.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EarcutrTriangulator;
+
+impl<Scalar: CoordFloat> Triangulator<Scalar> for EarcutrTriangulator {
+    fn triangulate(
+        &self,
+        input: TriangulationInput<Scalar>,
+    ) -> Result<Triangulation<Scalar>, crate::Error> {
+        let triangle_indices = earcutr::earcut(&input.vertices, &input.interior_indices, 2)
+            .map_err(crate::Error::Earcutr)?;
+        Ok(Triangulation {
+            vertices: input.vertices,
+            triangle_indices,
+        })
+    }
+}
@@ -0,0 +1,115 @@
+use super::{Refinement, Triangulation, TriangulationInput, Triangulator};
+use geo_types::CoordFloat;
+use spade::{
+    AngleLimit, ConstrainedDelaunayTriangulation, Point2, RefinementParameters, Triangulation as _,
+};
+
+/// Constrained Delaunay triangulation backed by spade. Slower than earcutr, but maximizes the
+/// minimum angle of the triangles and splits crossing rings instead of failing on them.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConstrainedDelaunayTriangulator {
+    refinement: Option<Refinement>,
+}
+
+impl ConstrainedDelaunayTriangulator {
+    /// Inserts Steiner points by Delaunay refinement until every triangle is within
+    /// `refinement`'s limits and has no angle below about 30°.
+    pub fn refined(refinement: Refinement) -> Self {
+        Self {
+            refinement: Some(refinement),
+        }
+    }
+}
+
+impl<Scalar: CoordFloat> Triangulator<Scalar> for ConstrainedDelaunayTriangulator {
+    fn triangulate(
+        &self,
+        input: TriangulationInput<Scalar>,
+    ) -> Result<Triangulation<Scalar>, crate::Error> {
+        let mut cdt = ConstrainedDelaunayTriangulation::<Point2<f64>>::new();
+        for ring in input.rings() {
+            let mut handles = Vec::with_capacity(ring.len() / 2);
+            for xy in ring.chunks_exact(2) {
+                let point = Point2::new(
+                    xy[0].to_f64().ok_or(crate::Error::CouldNotConvertToF64)?,
+                    xy[1].to_f64().ok_or(crate::Error::CouldNotConvertToF64)?,
+                );
+                handles.push(cdt.insert(point).map_err(crate::Error::Spade)?);
+            }
+            for i in 0..handles.len() {
+                let (from, to) = (handles[i], handles[(i + 1) % handles.len()]);
+                if from != to {
+                    cdt.add_constraint_and_split(from, to, |point| point);
+                }
+            }
+        }
+
+        let Some(refinement) = self.refinement else {
+            // Refining without any limits only determines which faces lie outside the rings.
+            let excluded_faces = cdt
+                .refine(
+                    RefinementParameters::new()
+                        .with_angle_limit(AngleLimit::from_deg(0.0))
+                        .with_max_additional_vertices(0)
+                        .keep_constraint_edges()
+                        .exclude_outer_faces(true),
+                )
+                .excluded_faces;
+            return Ok(delaunay_triangulation(&cdt, &excluded_faces));
+        };
+
+        // An equilateral triangle is the largest one whose edges all fit the length limit.
+        let max_area = refinement
+            .max_edge_length
+            .map(|length| 3f64.sqrt() / 4. * length * length)
+            .into_iter()
+            .chain(refinement.max_area)
+            .reduce(f64::min);
+        if !refinement.is_valid() {
+            return Err(crate::Error::InvalidRefinement(refinement));
+        }
+        let mut parameters = RefinementParameters::new()
+            .exclude_outer_faces(true)
+            .with_max_additional_vertices(
+                refinement.max_vertices.saturating_sub(cdt.num_vertices()),
+            );
+        if let Some(max_area) = max_area {
+            parameters = parameters.with_max_allowed_area(max_area);
+        }
+        let result = cdt.refine(parameters);
+        if !result.refinement_complete {
+            return Err(crate::Error::InvalidRefinement(refinement));
+        }
+
+        // The area limit alone still lets through the odd long, flat triangle.
+        delaunay_triangulation(&cdt, &result.excluded_faces).refine(&refinement)
+    }
+}
+
+fn delaunay_triangulation<Scalar: CoordFloat>(
+    cdt: &ConstrainedDelaunayTriangulation<Point2<f64>>,
+    excluded_faces: &[spade::handles::FixedFaceHandle<spade::handles::InnerTag>],
+) -> Triangulation<Scalar> {
+    let excluded_faces = excluded_faces
+        .iter()
+        .copied()
+        .collect::<std::collections::HashSet<_>>();
+
+    let mut vertices = Vec::with_capacity(cdt.num_vertices() * 2);
+    for vertex in cdt.vertices() {
+        let position = vertex.position();
+        vertices.push(Scalar::from(position.x).unwrap_or_else(Scalar::nan));
+        vertices.push(Scalar::from(position.y).unwrap_or_else(Scalar::nan));
+    }
+
+    let triangle_indices = cdt
+        .inner_faces()
+        .filter(|face| !excluded_faces.contains(&face.fix()))
+        .flat_map(|face| face.vertices().map(|vertex| vertex.fix().index()))
+        .collect();
+
+    Triangulation {
+        vertices,
+        triangle_indices,
+    }
+}
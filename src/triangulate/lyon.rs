@@ -0,0 +1,55 @@
+use super::{Triangulation, TriangulationInput, Triangulator};
+use geo_types::CoordFloat;
+use lyon_tessellation::{
+    math::point, path::Path, BuffersBuilder, FillOptions, FillTessellator, FillVertex,
+    VertexBuffers,
+};
+
+/// Sweep-line fill tessellation backed by lyon. Handles self-intersecting and overlapping
+/// rings with the even-odd rule, at `f32` precision.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LyonTriangulator;
+
+impl<Scalar: CoordFloat> Triangulator<Scalar> for LyonTriangulator {
+    fn triangulate(
+        &self,
+        input: TriangulationInput<Scalar>,
+    ) -> Result<Triangulation<Scalar>, crate::Error> {
+        let mut path_builder = Path::builder();
+        for ring in input.rings() {
+            let mut points = ring
+                .chunks_exact(2)
+                .map(|xy| Some(point(xy[0].to_f32()?, xy[1].to_f32()?)));
+            let Some(first) = points.next() else {
+                continue;
+            };
+            path_builder.begin(first.ok_or(crate::Error::CouldNotConvertToF32)?);
+            for to in points {
+                path_builder.line_to(to.ok_or(crate::Error::CouldNotConvertToF32)?);
+            }
+            path_builder.end(true);
+        }
+        let path = path_builder.build();
+
+        let mut buffers: VertexBuffers<[f32; 2], u32> = VertexBuffers::new();
+        FillTessellator::new()
+            .tessellate_path(
+                &path,
+                &FillOptions::even_odd(),
+                &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| {
+                    vertex.position().to_array()
+                }),
+            )
+            .map_err(crate::Error::Lyon)?;
+
+        Ok(Triangulation {
+            vertices: buffers
+                .vertices
+                .into_iter()
+                .flatten()
+                .map(|scalar| Scalar::from(scalar).unwrap_or_else(Scalar::nan))
+                .collect(),
+            triangle_indices: buffers.indices.into_iter().map(|i| i as usize).collect(),
+        })
+    }
+}
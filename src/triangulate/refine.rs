@@ -0,0 +1,161 @@
+use super::Triangulation;
+use geo_types::CoordFloat;
+use std::collections::HashMap;
+
+/// Upper bounds on the size of fill triangles, so the fill has vertices to displace or shade
+/// away from the polygon's rings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Refinement {
+    pub max_edge_length: Option<f64>,
+    pub max_area: Option<f64>,
+    /// Most vertices a refined polygon may have. Limits that would need more fail with
+    /// `Error::InvalidRefinement` rather than growing the fill without bound.
+    pub max_vertices: usize,
+}
+
+impl Default for Refinement {
+    fn default() -> Self {
+        Refinement {
+            max_edge_length: None,
+            max_area: None,
+            max_vertices: Self::DEFAULT_MAX_VERTICES,
+        }
+    }
+}
+
+impl Refinement {
+    pub const DEFAULT_MAX_VERTICES: usize = 1 << 20;
+
+    /// Whether the limits that are set are finite and positive.
+    pub fn is_valid(&self) -> bool {
+        [self.max_edge_length, self.max_area]
+            .into_iter()
+            .flatten()
+            .all(|limit| limit.is_finite() && limit > 0.)
+    }
+}
+
+impl<Scalar: CoordFloat> Triangulation<Scalar> {
+    /// Splits triangles at edge midpoints until none exceeds `refinement`'s limits.
+    ///
+    /// An edge is split for every triangle that shares it, so the result has no T-junctions.
+    /// Works on the output of any backend, but keeps the shape of the original triangles: use
+    /// `ConstrainedDelaunayTriangulator::refined` for well-shaped triangles.
+    ///
+    /// Fails with `Error::InvalidRefinement` if the limits aren't valid or can't be reached
+    /// within `Refinement::max_vertices`.
+    pub fn refine(mut self, refinement: &Refinement) -> Result<Self, crate::Error> {
+        if !refinement.is_valid() {
+            return Err(crate::Error::InvalidRefinement(*refinement));
+        }
+        let max_edge_length = refinement.max_edge_length.and_then(Scalar::from);
+        let max_area = refinement.max_area.and_then(Scalar::from);
+
+        loop {
+            // Midpoint vertices are numbered in the order their edges are first seen.
+            let first_midpoint = self.vertices.len() / 2;
+            let mut split_edges = HashMap::new();
+            let mut edges = Vec::new();
+            for triangle in self.triangle_indices.chunks_exact(3) {
+                let lengths = [0, 1, 2].map(|i| self.distance(triangle[i], triangle[(i + 1) % 3]));
+                let longest = (0..3).fold(0, |longest, i| {
+                    if lengths[i] > lengths[longest] {
+                        i
+                    } else {
+                        longest
+                    }
+                });
+                let too_large = max_area.is_some_and(|max| self.area(triangle) > max);
+                for (i, length) in lengths.into_iter().enumerate() {
+                    let split = max_edge_length.is_some_and(|max| length > max)
+                        || (too_large && i == longest);
+                    let key = edge_key(triangle, i);
+                    if split && !split_edges.contains_key(&key) {
+                        split_edges.insert(key, first_midpoint + edges.len());
+                        edges.push(key);
+                    }
+                }
+            }
+            if edges.is_empty() {
+                return Ok(self);
+            }
+            if self.vertices.len() / 2 + edges.len() > refinement.max_vertices {
+                return Err(crate::Error::InvalidRefinement(*refinement));
+            }
+
+            let two = Scalar::one() + Scalar::one();
+            for (a, b) in edges {
+                let (x, y) = (
+                    (self.vertices[a * 2] + self.vertices[b * 2]) / two,
+                    (self.vertices[a * 2 + 1] + self.vertices[b * 2 + 1]) / two,
+                );
+                self.vertices.push(x);
+                self.vertices.push(y);
+            }
+
+            let mut triangle_indices = Vec::with_capacity(self.triangle_indices.len() * 2);
+            for triangle in self.triangle_indices.chunks_exact(3) {
+                let midpoints = [0, 1, 2].map(|i| split_edges.get(&edge_key(triangle, i)).copied());
+                self.split_triangle(triangle, midpoints, &mut triangle_indices);
+            }
+            self.triangle_indices = triangle_indices;
+        }
+    }
+
+    /// Appends the triangles that replace `triangle` once the edges with a `midpoints` entry are
+    /// split. Edge `i` runs from vertex `i` to vertex `i + 1`; winding is preserved.
+    fn split_triangle(
+        &self,
+        triangle: &[usize],
+        midpoints: [Option<usize>; 3],
+        triangle_indices: &mut Vec<usize>,
+    ) {
+        let num_split = midpoints.iter().flatten().count();
+        // Rotate so that a lone split edge comes first, or a lone unsplit edge comes last.
+        let rotation = match num_split {
+            1 => midpoints.iter().position(Option::is_some).unwrap_or(0),
+            2 => (midpoints.iter().position(Option::is_none).unwrap_or(0) + 1) % 3,
+            _ => 0,
+        };
+        let [a, b, c] = [0, 1, 2].map(|i| triangle[(i + rotation) % 3]);
+        let [ab, bc, ca] = [0, 1, 2].map(|i| midpoints[(i + rotation) % 3]);
+
+        match (ab, bc, ca) {
+            (Some(ab), Some(bc), Some(ca)) => {
+                triangle_indices.extend([a, ab, ca, ab, b, bc, ca, bc, c, ab, bc, ca])
+            }
+            (Some(ab), Some(bc), None) => {
+                triangle_indices.extend([ab, b, bc]);
+                // Split the remaining quad along its shorter diagonal.
+                if self.distance(a, bc) <= self.distance(ab, c) {
+                    triangle_indices.extend([a, ab, bc, a, bc, c]);
+                } else {
+                    triangle_indices.extend([a, ab, c, ab, bc, c]);
+                }
+            }
+            (Some(ab), None, None) => triangle_indices.extend([a, ab, c, ab, b, c]),
+            _ => triangle_indices.extend([a, b, c]),
+        }
+    }
+
+    fn distance(&self, a: usize, b: usize) -> Scalar {
+        let dx = self.vertices[a * 2] - self.vertices[b * 2];
+        let dy = self.vertices[a * 2 + 1] - self.vertices[b * 2 + 1];
+        dx.hypot(dy)
+    }
+
+    fn area(&self, triangle: &[usize]) -> Scalar {
+        let [(ax, ay), (bx, by), (cx, cy)] = [0, 1, 2].map(|i| {
+            (
+                self.vertices[triangle[i] * 2],
+                self.vertices[triangle[i] * 2 + 1],
+            )
+        });
+        ((bx - ax) * (cy - ay) - (by - ay) * (cx - ax)).abs() / (Scalar::one() + Scalar::one())
+    }
+}
+
+fn edge_key(triangle: &[usize], i: usize) -> (usize, usize) {
+    let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+    (a.min(b), a.max(b))
+}
@@ -0,0 +1,412 @@
+use geo_traits::{CoordTrait, LineStringTrait, PolygonTrait};
+use geo_types::{Coord, CoordFloat, LineString, MultiPolygon, Polygon};
+
+/// Controls what `PolygonMeshBuilder::add_polygon` does with its input before triangulation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Validation {
+    /// Triangulate the input as-is.
+    #[default]
+    Off,
+    /// Return `Error::InvalidGeometry` for input that would not mesh correctly.
+    Reject,
+    /// Repair the input with [`repair_polygon`] before triangulating it.
+    Repair,
+}
+
+/// Identifies a ring of a polygon.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ring {
+    Exterior,
+    Interior(usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationIssue {
+    /// The first and last coordinates of the ring differ.
+    UnclosedRing(Ring),
+    /// The ring has fewer than three distinct coordinates.
+    TooFewCoords { ring: Ring, num_coords: usize },
+    /// The ring repeats a coordinate `count` times in a row.
+    DuplicatePoints { ring: Ring, count: usize },
+    /// The ring has `count` vertices lying on the line through their neighbours.
+    CollinearPoints { ring: Ring, count: usize },
+    /// The exterior is not counter-clockwise, or an interior is not clockwise.
+    WrongWinding(Ring),
+    /// Two non-adjacent segments of the ring meet at `at`.
+    SelfIntersection { ring: Ring, at: [f64; 2] },
+    /// The interior ring at this index is not contained in the exterior: it has a coordinate
+    /// outside it or crosses it.
+    HoleOutsideShell(usize),
+    /// The interior rings at these indices cross, or one lies inside the other.
+    OverlappingHoles(usize, usize),
+}
+
+impl ValidationIssue {
+    /// Whether the issue makes the polygon mesh incorrectly. Winding, duplicate and collinear
+    /// points only cost extra vertices.
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            ValidationIssue::UnclosedRing(_)
+                | ValidationIssue::TooFewCoords { .. }
+                | ValidationIssue::SelfIntersection { .. }
+                | ValidationIssue::HoleOutsideShell(_)
+                | ValidationIssue::OverlappingHoles(..)
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        !self.issues.iter().any(ValidationIssue::is_fatal)
+    }
+}
+
+/// Checks `polygon` for everything that makes earcutr or the outline meshes produce bad output.
+///
+/// Self-intersection checks are quadratic in the number of coordinates per ring, and checks
+/// between rings in the number of coordinates per pair of rings.
+pub fn validate_polygon<Scalar: CoordFloat>(
+    polygon: &impl PolygonTrait<T = Scalar>,
+) -> ValidationReport {
+    let mut issues = Vec::new();
+    let exterior = polygon
+        .exterior()
+        .map(|exterior| ring_coords(&exterior))
+        .unwrap_or_default();
+    validate_ring(&exterior, Ring::Exterior, &mut issues);
+
+    let shell = clean_ring(open_ring(exterior));
+    let mut holes: Vec<Vec<Coord<Scalar>>> = Vec::new();
+    for (i, interior) in polygon.interiors().enumerate() {
+        let coords = ring_coords(&interior);
+        validate_ring(&coords, Ring::Interior(i), &mut issues);
+        let hole = clean_ring(open_ring(coords));
+        if shell.len() >= 3 && hole.len() >= 3 && !contains(&shell, &hole) {
+            issues.push(ValidationIssue::HoleOutsideShell(i));
+        }
+        for (j, other) in holes.iter().enumerate() {
+            if overlap(other, &hole) {
+                issues.push(ValidationIssue::OverlappingHoles(j, i));
+            }
+        }
+        holes.push(hole);
+    }
+
+    ValidationReport { issues }
+}
+
+/// Returns a valid version of `polygon` together with the issues found in the original.
+///
+/// Rings are closed, stripped of duplicate and collinear points and wound counter-clockwise
+/// (exterior) or clockwise (interiors). Self-intersecting rings are split at each crossing, so
+/// a self-intersecting exterior can turn into several polygons. Rings left with fewer than three
+/// coordinates, holes outside every shell and holes overlapping an earlier hole are dropped.
+pub fn repair_polygon<Scalar: CoordFloat>(
+    polygon: &impl PolygonTrait<T = Scalar>,
+) -> (MultiPolygon<Scalar>, ValidationReport) {
+    let report = validate_polygon(polygon);
+
+    let shells = polygon
+        .exterior()
+        .map(|exterior| repair_ring(ring_coords(&exterior), true))
+        .unwrap_or_default();
+    let mut holes: Vec<Vec<Vec<Coord<Scalar>>>> = vec![Vec::new(); shells.len()];
+    for interior in polygon.interiors() {
+        for hole in repair_ring(ring_coords(&interior), false) {
+            let shell_index = shells.iter().position(|shell| contains(shell, &hole));
+            if let Some(shell_index) = shell_index {
+                if !holes[shell_index].iter().any(|other| overlap(other, &hole)) {
+                    holes[shell_index].push(hole);
+                }
+            }
+        }
+    }
+
+    let polygons = shells
+        .into_iter()
+        .zip(holes)
+        .map(|(shell, holes)| {
+            Polygon::new(
+                LineString::new(shell),
+                holes.into_iter().map(LineString::new).collect(),
+            )
+        })
+        .collect();
+
+    (MultiPolygon::new(polygons), report)
+}
+
+fn validate_ring<Scalar: CoordFloat>(
+    coords: &[Coord<Scalar>],
+    ring: Ring,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    if coords.len() > 1 && coords.first() != coords.last() {
+        issues.push(ValidationIssue::UnclosedRing(ring));
+    }
+
+    let open = open_ring(coords.to_vec());
+    let duplicates = (0..open.len())
+        .filter(|&i| open.len() > 1 && open[i] == open[(i + 1) % open.len()])
+        .count();
+    if duplicates > 0 {
+        issues.push(ValidationIssue::DuplicatePoints {
+            ring,
+            count: duplicates,
+        });
+    }
+
+    let deduped = dedup_ring(open);
+    if deduped.len() < 3 {
+        issues.push(ValidationIssue::TooFewCoords {
+            ring,
+            num_coords: coords.len(),
+        });
+        return;
+    }
+
+    let n = deduped.len();
+    let collinear = (0..n)
+        .filter(|&i| orient(deduped[(i + n - 1) % n], deduped[i], deduped[(i + 1) % n]).is_zero())
+        .count();
+    if collinear > 0 {
+        issues.push(ValidationIssue::CollinearPoints {
+            ring,
+            count: collinear,
+        });
+    }
+
+    let area = signed_area(&deduped);
+    let counter_clockwise = ring == Ring::Exterior;
+    if !area.is_zero() && (area > Scalar::zero()) != counter_clockwise {
+        issues.push(ValidationIssue::WrongWinding(ring));
+    }
+
+    for (i, j) in non_adjacent_segments(n) {
+        if let Some(at) = segment_intersection(
+            deduped[i],
+            deduped[(i + 1) % n],
+            deduped[j],
+            deduped[(j + 1) % n],
+        ) {
+            issues.push(ValidationIssue::SelfIntersection {
+                ring,
+                at: [
+                    at.x.to_f64().unwrap_or(f64::NAN),
+                    at.y.to_f64().unwrap_or(f64::NAN),
+                ],
+            });
+        }
+    }
+}
+
+/// Splits `coords` into simple open rings wound in the requested direction.
+fn repair_ring<Scalar: CoordFloat>(
+    coords: Vec<Coord<Scalar>>,
+    counter_clockwise: bool,
+) -> Vec<Vec<Coord<Scalar>>> {
+    let mut rings = Vec::new();
+    split_self_intersections(open_ring(coords), &mut rings);
+    for ring in &mut rings {
+        if (signed_area(ring) > Scalar::zero()) != counter_clockwise {
+            ring.reverse();
+        }
+    }
+    rings
+}
+
+fn split_self_intersections<Scalar: CoordFloat>(
+    ring: Vec<Coord<Scalar>>,
+    rings: &mut Vec<Vec<Coord<Scalar>>>,
+) {
+    let ring = clean_ring(ring);
+    if ring.len() < 3 {
+        return;
+    }
+
+    let n = ring.len();
+    let crossing = non_adjacent_segments(n).find_map(|(i, j)| {
+        segment_intersection(ring[i], ring[(i + 1) % n], ring[j], ring[(j + 1) % n])
+            .map(|at| (i, j, at))
+    });
+
+    match crossing {
+        None => rings.push(ring),
+        Some((i, j, at)) => {
+            // Both halves are strictly shorter than `ring`, so this terminates.
+            let mut first = vec![at];
+            first.extend_from_slice(&ring[i + 1..=j]);
+            let mut second = vec![at];
+            second.extend_from_slice(&ring[j + 1..]);
+            second.extend_from_slice(&ring[..=i]);
+            split_self_intersections(first, rings);
+            split_self_intersections(second, rings);
+        }
+    }
+}
+
+fn ring_coords<Scalar: CoordFloat>(ring: &impl LineStringTrait<T = Scalar>) -> Vec<Coord<Scalar>> {
+    ring.coords()
+        .map(|coord| Coord {
+            x: coord.x(),
+            y: coord.y(),
+        })
+        .collect()
+}
+
+fn open_ring<Scalar: CoordFloat>(mut coords: Vec<Coord<Scalar>>) -> Vec<Coord<Scalar>> {
+    if coords.len() > 1 && coords.first() == coords.last() {
+        coords.pop();
+    }
+    coords
+}
+
+fn dedup_ring<Scalar: CoordFloat>(mut ring: Vec<Coord<Scalar>>) -> Vec<Coord<Scalar>> {
+    ring.dedup();
+    while ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    ring
+}
+
+/// Removes duplicate and collinear vertices from an open ring.
+fn clean_ring<Scalar: CoordFloat>(ring: Vec<Coord<Scalar>>) -> Vec<Coord<Scalar>> {
+    let mut ring = dedup_ring(ring);
+    loop {
+        let len = ring.len();
+        let mut i = 0;
+        while ring.len() >= 3 && i < ring.len() {
+            let n = ring.len();
+            let (prev, next) = (ring[(i + n - 1) % n], ring[(i + 1) % n]);
+            if ring[i] == next || orient(prev, ring[i], next).is_zero() {
+                ring.remove(i);
+                // Removing a vertex can make the previous one collinear.
+                i = i.saturating_sub(1);
+            } else {
+                i += 1;
+            }
+        }
+        if ring.len() == len {
+            return ring;
+        }
+    }
+}
+
+/// Pairs of segment indices of an open ring with `n` vertices that share no vertex.
+fn non_adjacent_segments(n: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..n).flat_map(move |i| {
+        (i + 2..n)
+            .filter(move |&j| !(i == 0 && j == n - 1))
+            .map(move |j| (i, j))
+    })
+}
+
+fn orient<Scalar: CoordFloat>(a: Coord<Scalar>, b: Coord<Scalar>, c: Coord<Scalar>) -> Scalar {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+fn signed_area<Scalar: CoordFloat>(ring: &[Coord<Scalar>]) -> Scalar {
+    let n = ring.len();
+    let twice_area = (0..n).fold(Scalar::zero(), |sum, i| {
+        let (a, b) = (ring[i], ring[(i + 1) % n]);
+        sum + a.x * b.y - b.x * a.y
+    });
+    twice_area / (Scalar::one() + Scalar::one())
+}
+
+fn on_segment<Scalar: CoordFloat>(a: Coord<Scalar>, b: Coord<Scalar>, p: Coord<Scalar>) -> bool {
+    orient(a, b, p).is_zero()
+        && p.x >= a.x.min(b.x)
+        && p.x <= a.x.max(b.x)
+        && p.y >= a.y.min(b.y)
+        && p.y <= a.y.max(b.y)
+}
+
+fn segment_intersection<Scalar: CoordFloat>(
+    a: Coord<Scalar>,
+    b: Coord<Scalar>,
+    c: Coord<Scalar>,
+    d: Coord<Scalar>,
+) -> Option<Coord<Scalar>> {
+    let (o1, o2) = (orient(a, b, c), orient(a, b, d));
+    let (o3, o4) = (orient(c, d, a), orient(c, d, b));
+
+    if o1 * o2 < Scalar::zero() && o3 * o4 < Scalar::zero() {
+        let t = o3 / (o3 - o4);
+        return Some(Coord {
+            x: a.x + t * (b.x - a.x),
+            y: a.y + t * (b.y - a.y),
+        });
+    }
+
+    [(a, b, c), (a, b, d), (c, d, a), (c, d, b)]
+        .into_iter()
+        .find(|&(start, end, p)| on_segment(start, end, p))
+        .map(|(_, _, p)| p)
+}
+
+/// Whether the open ring `inner` lies within `outer`, touching it at most at points.
+fn contains<Scalar: CoordFloat>(outer: &[Coord<Scalar>], inner: &[Coord<Scalar>]) -> bool {
+    inner
+        .iter()
+        .all(|coord| locate(*coord, outer) != Location::Outside)
+        && !rings_cross(outer, inner)
+}
+
+/// Whether the open rings `a` and `b` cross, or either lies inside the other.
+fn overlap<Scalar: CoordFloat>(a: &[Coord<Scalar>], b: &[Coord<Scalar>]) -> bool {
+    rings_cross(a, b)
+        || a.iter().any(|coord| locate(*coord, b) == Location::Inside)
+        || b.iter().any(|coord| locate(*coord, a) == Location::Inside)
+}
+
+/// Whether a segment of open ring `a` crosses one of `b` at a point inside both.
+fn rings_cross<Scalar: CoordFloat>(a: &[Coord<Scalar>], b: &[Coord<Scalar>]) -> bool {
+    segments(a).any(|(p, q)| {
+        segments(b).any(|(r, s)| {
+            orient(p, q, r) * orient(p, q, s) < Scalar::zero()
+                && orient(r, s, p) * orient(r, s, q) < Scalar::zero()
+        })
+    })
+}
+
+/// The segments of an open ring, closing it.
+fn segments<Scalar: CoordFloat>(
+    ring: &[Coord<Scalar>],
+) -> impl Iterator<Item = (Coord<Scalar>, Coord<Scalar>)> + '_ {
+    ring.iter()
+        .copied()
+        .zip(ring.iter().copied().cycle().skip(1))
+}
+
+#[derive(PartialEq)]
+enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+fn locate<Scalar: CoordFloat>(p: Coord<Scalar>, ring: &[Coord<Scalar>]) -> Location {
+    let n = ring.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (a, b) = (ring[i], ring[(i + 1) % n]);
+        if on_segment(a, b, p) {
+            return Location::Boundary;
+        }
+        if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+            inside = !inside;
+        }
+    }
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
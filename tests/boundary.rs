@@ -0,0 +1,102 @@
+mod common;
+
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::Mesh;
+use common::{positions, square_with_hole};
+use geo_bevy::*;
+use geo_types::geometry::*;
+
+fn fill(polygon: Polygon, boundary_distances: Option<f64>) -> Mesh {
+    let options = MeshOptions {
+        polygon_mode: PolygonMode::Fill,
+        boundary_distances,
+        ..Default::default()
+    };
+    let mesh = polygon_to_mesh_with_options(polygon, options).expect("Mesh");
+    mesh.mesh.expect("Fill")
+}
+
+fn distances(mesh: &Mesh) -> &[f32] {
+    match mesh.attribute(ATTRIBUTE_BOUNDARY_DISTANCE) {
+        Some(VertexAttributeValues::Float32(distances)) => distances,
+        _ => panic!("Expected boundary distances."),
+    }
+}
+
+#[test]
+pub fn measures_distances_to_the_exterior() {
+    let mesh = fill(Rect::new([0., 0.], [10., 10.]).to_polygon(), Some(1.));
+    // Refinement adds vertices inside the square, down to its center.
+    assert!(positions(&mesh).len() > 4);
+    assert!(positions(&mesh).contains(&[5., 5., 0.]));
+    for ([x, y, _], distance) in positions(&mesh).iter().zip(distances(&mesh)) {
+        let expected = x.min(10. - x).min(*y).min(10. - y);
+        assert!((distance - expected).abs() < 1e-4);
+    }
+    assert_eq!(Some(5.), distances(&mesh).iter().copied().reduce(f32::max));
+}
+
+#[test]
+pub fn measures_distances_to_holes() {
+    let mesh = fill(square_with_hole(12., 4., 8.), Some(1.));
+    for ([x, y, _], distance) in positions(&mesh).iter().zip(distances(&mesh)) {
+        let to_exterior = x.min(12. - x).min(*y).min(12. - y);
+        let (dx, dy) = ((4. - x).max(x - 8.).max(0.), (4. - y).max(y - 8.).max(0.));
+        let expected = to_exterior.min(dx.hypot(dy));
+        assert!((distance - expected).abs() < 1e-4);
+    }
+}
+
+#[test]
+pub fn leaves_fills_without_distances_by_default() {
+    let mesh = fill(Rect::new([0., 0.], [10., 10.]).to_polygon(), None);
+    assert_eq!(4, positions(&mesh).len());
+    assert!(mesh.attribute(ATTRIBUTE_BOUNDARY_DISTANCE).is_none());
+
+    // Patterns get distances too.
+    let options = MeshOptions {
+        polygon_mode: PolygonMode::Fill,
+        boundary_distances: Some(2.),
+        fill_pattern: Some(FillPattern::hatch(0., 4., 1.)),
+        ..Default::default()
+    };
+    let mesh = polygon_to_mesh_with_options(Rect::new([0., 0.], [10., 10.]).to_polygon(), options)
+        .expect("Mesh")
+        .mesh
+        .expect("Fill");
+    assert_eq!(positions(&mesh).len(), distances(&mesh).len());
+}
+
+#[test]
+pub fn measures_distances_to_large_rings() {
+    // A circle of radius 100 with a square hole, both finely divided.
+    let ring = |count: usize, point: &dyn Fn(f64) -> [f64; 2]| {
+        LineString::from(
+            (0..count)
+                .map(|i| point(i as f64 / count as f64))
+                .collect::<Vec<_>>(),
+        )
+    };
+    let circle = ring(8192, &|t| {
+        let angle = t * std::f64::consts::TAU;
+        [100. * angle.cos(), 100. * angle.sin()]
+    });
+    let hole = ring(256, &|t| match (t * 4.) as usize {
+        0 => [-10. + 80. * t, -10.],
+        1 => [10., -10. + 80. * (t - 0.25)],
+        2 => [10. - 80. * (t - 0.5), 10.],
+        _ => [-10., 10. - 80. * (t - 0.75)],
+    });
+    let mesh = fill(Polygon::new(circle, vec![hole]), Some(20.));
+    assert!(positions(&mesh).len() > 8192 + 256);
+    for ([x, y, _], distance) in positions(&mesh).iter().zip(distances(&mesh)) {
+        let to_exterior = 100. - x.hypot(*y);
+        let (dx, dy) = ((x.abs() - 10.).max(0.), (y.abs() - 10.).max(0.));
+        let to_hole = match dx.max(dy) > 0. {
+            true => dx.hypot(dy),
+            false => 10. - x.abs().max(y.abs()),
+        };
+        // Within the sagitta of the circle's chords.
+        assert!((distance - to_exterior.min(to_hole)).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,124 @@
+use geo_bevy::*;
+use geo_types::geometry::*;
+
+#[test]
+pub fn streams_geometries_into_context() {
+    let mut ctx = BuildBevyMeshesContext::<f64>::new();
+    for i in 0..3 {
+        let offset = i as f64;
+        ctx.add_geometry(&Geometry::LineString(
+            vec![[offset, 0.], [offset, 1.]].into(),
+        ))
+        .expect("Vertices");
+    }
+    assert_eq!(6, ctx.line_string_mesh_builder.len());
+
+    let Ok(GeometryMesh::LineString(mesh)) = ctx.build_ref() else {
+        panic!("Expected a line string mesh.")
+    };
+    assert_eq!(6, mesh.count_vertices());
+    // Building leaves the builder as it was.
+    assert_eq!(6, ctx.len());
+}
+
+#[test]
+pub fn reuses_allocations_after_clear() {
+    let mut builder = PolygonMeshBuilder::<f64>::new();
+    builder
+        .add_polygon(&Polygon::new(
+            vec![[0., 0.], [1., 0.], [1., 1.], [0., 1.]].into(),
+            vec![],
+        ))
+        .expect("Vertices");
+    let capacity = builder.capacity();
+    assert!(PolygonMesh::try_from(&builder).is_ok());
+
+    builder.clear();
+    assert!(builder.is_empty());
+    assert_eq!(capacity, builder.capacity());
+    assert!(matches!(builder.build(), Err(Error::EmptyGeometry)));
+}
+
+#[test]
+pub fn builds_points_and_lines_incrementally() {
+    let mut points = PointMeshBuilder::new();
+    points.add_point(&Point::new(1., 2.));
+    let Ok(GeometryMesh::Point(positions)) = points.build() else {
+        panic!("Expected points.")
+    };
+    assert_eq!(vec![SpritePosition::new(1., 2.)], positions);
+
+    let mut lines = LineStringMeshBuilder::new();
+    lines
+        .add_line(&Line::new([0., 0.], [1., 0.]))
+        .expect("Vertices");
+    lines
+        .add_line_string(&LineString::from(vec![[0., 1.], [1., 1.], [1., 2.]]))
+        .expect("Vertices");
+    assert_eq!(5, lines.len());
+    assert!(lines.capacity() >= 5);
+}
+
+#[test]
+pub fn reserves_for_whole_geometry_up_front() {
+    let square = |offset: f64| {
+        Polygon::new(
+            vec![
+                [offset, 0.],
+                [offset + 1., 0.],
+                [offset + 1., 1.],
+                [offset, 1.],
+            ]
+            .into(),
+            vec![],
+        )
+    };
+    let geometry = Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+        Geometry::MultiPolygon((0..100).map(|i| square(2. * i as f64)).collect()),
+        Geometry::LineString(vec![[0., 0.], [1., 1.], [2., 0.]].into()),
+        Geometry::Rect(Rect::new([0., 0.], [1., 1.])),
+        Geometry::MultiPoint(vec![[0., 0.], [1., 1.]].into()),
+    ]));
+
+    let mut ctx = BuildBevyMeshesContext::<f64>::new();
+    ctx.reserve_for(&geometry);
+    let capacities = [
+        ctx.point_mesh_builder.capacity(),
+        ctx.line_string_mesh_builder.capacity(),
+        ctx.polygon_mesh_builder.capacity(),
+    ];
+
+    ctx.add_geometry(&geometry).expect("Vertices");
+    // Fills leave out the repeated closing coordinate of each square.
+    assert_eq!(100 * 4 + 4, ctx.polygon_mesh_builder.len());
+    assert_eq!(
+        capacities,
+        [
+            ctx.point_mesh_builder.capacity(),
+            ctx.line_string_mesh_builder.capacity(),
+            ctx.polygon_mesh_builder.capacity(),
+        ]
+    );
+}
+
+#[test]
+pub fn builds_point_instances() {
+    let mut builder = PointMeshBuilder::new();
+    builder.add_point(&Point::new(1., 2.));
+    builder.add_point(&Point::new(3., 4.));
+    let mut instances = builder.instances(2., bevy::color::LinearRgba::RED);
+    assert_eq!(
+        PointInstance {
+            position: [1., 2., 0.],
+            size: 2.,
+            color: [1., 0., 0., 1.],
+            rotation: 0.,
+        },
+        instances[0]
+    );
+    instances[1] = instances[1].with_rotation(1.);
+    assert_eq!([3., 4., 0.], instances[1].position);
+    assert_eq!(1., instances[1].rotation);
+    // Tightly packed, as the instance buffer is.
+    assert_eq!(36, std::mem::size_of::<PointInstance>());
+}
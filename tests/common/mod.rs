@@ -0,0 +1,28 @@
+//! Helpers shared by the integration tests. Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::Mesh;
+use geo_types::geometry::Polygon;
+
+pub fn positions(mesh: &Mesh) -> &[[f32; 3]] {
+    match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => positions,
+        _ => panic!("Expected positions."),
+    }
+}
+
+/// A square from the origin to `(size, size)`, with a square hole from `(hole_min, hole_min)` to
+/// `(hole_max, hole_max)`.
+pub fn square_with_hole(size: f64, hole_min: f64, hole_max: f64) -> Polygon {
+    Polygon::new(
+        vec![[0., 0.], [size, 0.], [size, size], [0., size]].into(),
+        vec![vec![
+            [hole_min, hole_min],
+            [hole_min, hole_max],
+            [hole_max, hole_max],
+            [hole_max, hole_min],
+        ]
+        .into()],
+    )
+}
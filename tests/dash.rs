@@ -0,0 +1,160 @@
+mod common;
+
+#[cfg(feature = "material")]
+use bevy::color::LinearRgba;
+use bevy::mesh::{Indices, VertexAttributeValues};
+use bevy::prelude::Mesh;
+use common::positions;
+use geo_bevy::*;
+use geo_types::geometry::*;
+
+fn segments(mesh: &Mesh) -> Vec<[[f32; 2]; 2]> {
+    let positions = positions(mesh);
+    let Some(Indices::U32(indices)) = mesh.indices() else {
+        panic!("Expected u32 indices.")
+    };
+    indices
+        .chunks_exact(2)
+        .map(|segment| {
+            let xy = |i: u32| [positions[i as usize][0], positions[i as usize][1]];
+            [xy(segment[0]), xy(segment[1])]
+        })
+        .collect()
+}
+
+fn dashed(lengths: impl Into<Vec<f64>>, offset: f64) -> MeshOptions {
+    MeshOptions {
+        dash: Some(DashPattern::new(lengths, DashUnits::Geo).with_offset(offset)),
+        ..Default::default()
+    }
+}
+
+#[test]
+pub fn splits_line_strings_into_dashes() {
+    let line_string = LineString::from(vec![[0., 0.], [4., 0.], [4., 6.]]);
+    let mesh = line_string_to_mesh_with_options(line_string, dashed([2., 1.], 0.)).expect("Dashes");
+    assert_eq!(
+        vec![
+            [[0., 0.], [2., 0.]],
+            [[3., 0.], [4., 0.]],
+            // Dashes carry on around corners.
+            [[4., 0.], [4., 1.]],
+            [[4., 2.], [4., 4.]],
+            [[4., 5.], [4., 6.]],
+        ],
+        segments(&mesh)
+    );
+}
+
+#[test]
+pub fn repeats_odd_patterns_from_offset() {
+    // A single length is both the dash and the gap.
+    let line = Line::new([0., 0.], [4., 0.]);
+    let mesh = line_to_mesh_with_options(line, dashed([1.], 0.5)).expect("Dashes");
+    assert_eq!(
+        vec![
+            [[0., 0.], [0.5, 0.]],
+            [[1.5, 0.], [2.5, 0.]],
+            [[3.5, 0.], [4., 0.]],
+        ],
+        segments(&mesh)
+    );
+
+    // Rings are dashed across their closing segment too.
+    let square = Rect::new([0., 0.], [1., 1.]);
+    let outline = rect_to_mesh_with_options(square, dashed([1.5, 0.5], 0.))
+        .expect("Dashes")
+        .exterior_mesh
+        .expect("Outline");
+    assert_eq!(
+        vec![
+            [[0., 0.], [0., 1.]],
+            [[0., 1.], [0.5, 1.]],
+            [[1., 1.], [1., 0.]],
+            [[1., 0.], [0.5, 0.]],
+        ],
+        segments(&outline)
+    );
+}
+
+#[test]
+pub fn adds_distances_for_screen_space_dashes() {
+    let options = MeshOptions {
+        dash: Some(DashPattern::new([4., 2.], DashUnits::Screen)),
+        ..Default::default()
+    };
+    let line_string = LineString::from(vec![[0., 0.], [3., 0.], [3., 4.]]);
+    let mesh = line_string_to_mesh_with_options(line_string, options.clone()).expect("Vertices");
+    assert_eq!(2, segments(&mesh).len());
+    let Some(VertexAttributeValues::Float32(distances)) = mesh.attribute(ATTRIBUTE_LINE_DISTANCE)
+    else {
+        panic!("Expected distances.")
+    };
+    assert_eq!(&vec![0., 3., 7.], distances);
+
+    // Rings repeat their first vertex, so the closing segment ends at the perimeter.
+    let outline = rect_to_mesh_with_options(Rect::new([0., 0.], [1., 2.]), options)
+        .expect("Vertices")
+        .exterior_mesh
+        .expect("Outline");
+    let Some(VertexAttributeValues::Float32(distances)) =
+        outline.attribute(ATTRIBUTE_LINE_DISTANCE)
+    else {
+        panic!("Expected distances.")
+    };
+    assert_eq!(&vec![0., 2., 3., 5., 6.], distances);
+}
+
+#[test]
+pub fn adds_progress_along_each_line_string() {
+    let options = MeshOptions {
+        merge_line_strings: true,
+        line_distances: true,
+        ..dashed([1.], 0.)
+    };
+    let multi_line_string = MultiLineString::new(vec![
+        vec![[0., 0.], [4., 0.]].into(),
+        vec![[0., 1.], [0., 3.]].into(),
+    ]);
+    let meshes = multi_line_string_to_mesh_with_options(multi_line_string, options).expect("Mesh");
+    let attribute = |attribute| match meshes[0].attribute(attribute) {
+        Some(VertexAttributeValues::Float32(values)) => values.clone(),
+        _ => panic!("Expected {attribute:?}."),
+    };
+    // Gaps keep counting towards both.
+    assert_eq!(
+        vec![0., 1., 2., 3., 0., 1.],
+        attribute(ATTRIBUTE_LINE_DISTANCE)
+    );
+    assert_eq!(
+        vec![0., 0.25, 0.5, 0.75, 0., 0.5],
+        attribute(ATTRIBUTE_LINE_PROGRESS)
+    );
+}
+
+#[test]
+pub fn rejects_invalid_patterns() {
+    let line_string = LineString::from(vec![[0., 0.], [4., 0.]]);
+    for lengths in [vec![], vec![0., 0.], vec![1., f64::NAN], vec![2., -1.]] {
+        assert!(matches!(
+            line_string_to_mesh_with_options(line_string.clone(), dashed(lengths, 0.)),
+            Err(Error::InvalidDashPattern(_))
+        ));
+    }
+    let outline = rect_to_mesh_with_options(Rect::new([0., 0.], [1., 1.]), dashed([1.], f64::NAN));
+    assert!(matches!(outline, Err(Error::InvalidDashPattern(_))));
+
+    #[cfg(feature = "material")]
+    {
+        let screen = |lengths: &[f64]| DashPattern::new(lengths, DashUnits::Screen);
+        let material =
+            DashedLineMaterial::new(LinearRgba::WHITE, &screen(&[4., 2., 1.])).expect("Material");
+        assert_eq!(14., material.line.period);
+        assert_eq!([4., 2., 1., 4.], material.line.lengths[0].to_array());
+        assert_eq!([2., 1., 0., 0.], material.line.lengths[1].to_array());
+        assert!(DashedLineMaterial::new(LinearRgba::WHITE, &screen(&[1.; 9])).is_err());
+        assert!(
+            DashedLineMaterial::new(LinearRgba::WHITE, &dashed([1.], 0.).dash.unwrap()).is_err()
+        );
+    }
+}
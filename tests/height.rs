@@ -0,0 +1,153 @@
+mod common;
+
+use bevy::{mesh::VertexAttributeValues, prelude::Mesh};
+use common::positions;
+use geo_bevy::*;
+use geo_types::geometry::*;
+use std::sync::Arc;
+
+/// 3x3 pixels of size 1 covering `(0, 0)..(3, 3)`, rising by 1 per column.
+fn slope() -> HeightmapGrid {
+    HeightmapGrid::new(
+        3,
+        3,
+        vec![0., 1., 2., 0., 1., 2., 0., 1., 2.],
+        GeoTransform::north_up([0., 3.], [1., 1.]),
+    )
+    .expect("3x3 values")
+}
+
+#[test]
+pub fn samples_heightmap_between_pixel_centres() {
+    let heightmap = slope();
+    assert_eq!(Some(0.), heightmap.height_at(0.5, 0.5));
+    assert_eq!(Some(1.5), heightmap.height_at(2., 1.2));
+    assert_eq!(None, heightmap.height_at(0.2, 1.));
+    assert_eq!(Some(1.), heightmap.resolution());
+    assert_eq!(Some(2.), heightmap.value(2, 0));
+    // Past the end of the row, not wrapped onto the next.
+    assert_eq!(None, heightmap.value(3, 0));
+
+    assert!(matches!(
+        HeightmapGrid::new(3, 2, vec![0.; 9], heightmap.transform()),
+        Err(Error::HeightmapSizeMismatch { num_values: 9, .. })
+    ));
+}
+
+#[test]
+pub fn drapes_and_resamples_line_string() {
+    let line_string = LineString::from(vec![[0.5, 1.5], [2.5, 1.5]]);
+    let GeometryMesh::LineString(mesh) =
+        geometry_to_mesh_with_height_source(line_string, Arc::new(slope())).expect("Vertices")
+    else {
+        panic!("Expected a line string mesh.")
+    };
+
+    assert_eq!(
+        &vec![[0.5, 1.5, 0.], [1.5, 1.5, 1.], [2.5, 1.5, 2.]],
+        positions(&mesh)
+    );
+}
+
+#[test]
+pub fn drapes_polygon_fill() {
+    let polygon = Polygon::new(
+        vec![[0.5, 0.5], [2.5, 0.5], [2.5, 2.5], [0.5, 2.5]].into(),
+        vec![],
+    );
+    let GeometryMesh::Polygon(PolygonMesh { mesh, .. }) =
+        geometry_to_mesh_with_height_source(polygon, Arc::new(slope())).expect("Vertices")
+    else {
+        panic!("Expected a polygon mesh.")
+    };
+
+    for [x, _, z] in positions(&mesh) {
+        assert_eq!(x - 0.5, *z);
+    }
+}
+
+#[test]
+pub fn samples_image_as_dem() {
+    use bevy::{
+        asset::RenderAssetUsages,
+        image::Image,
+        render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+
+    let image = Image::new(
+        Extent3d {
+            width: 2,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        [0.25f32, 0.75]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect(),
+        TextureFormat::R32Float,
+        RenderAssetUsages::default(),
+    );
+    let heightmap =
+        ImageHeightmap::new(image, GeoTransform::north_up([0., 1.], [1., 1.]), 100., 10.)
+            .expect("Supported format");
+
+    assert_eq!(Some(35.), heightmap.height_at(0.5, 0.5));
+    assert_eq!(Some(60.), heightmap.height_at(1., 0.5));
+}
+
+#[test]
+pub fn builds_terrain_matching_draped_heights() {
+    let heightmap = slope();
+    let terrain = terrain_to_mesh(&heightmap).expect("Vertices");
+
+    let positions = positions(&terrain);
+    assert_eq!(9, positions.len());
+    assert_eq!([0.5, 2.5, 0.], positions[0]);
+    assert_eq!([2.5, 0.5, 2.], positions[8]);
+    for [x, y, z] in positions {
+        assert_eq!(heightmap.height_at(*x as f64, *y as f64), Some(*z));
+    }
+
+    let Some(VertexAttributeValues::Float32x3(normals)) = terrain.attribute(Mesh::ATTRIBUTE_NORMAL)
+    else {
+        panic!("Expected normals.")
+    };
+    // The slope rises along x, so normals lean towards -x and point up.
+    assert!(normals
+        .iter()
+        .all(|normal| normal[0] < 0. && normal[2] > 0.));
+}
+
+#[test]
+pub fn splits_terrain_at_u16_limit() {
+    use bevy::{asset::RenderAssetUsages, mesh::Indices};
+
+    let size = 300;
+    let heightmap = HeightmapGrid::new(
+        size,
+        size,
+        vec![0.; size * size],
+        GeoTransform::north_up([0., size as f64], [1., 1.]),
+    )
+    .expect("Values");
+    let options = MeshOptions {
+        index_width: IndexWidth::U16,
+        asset_usages: RenderAssetUsages::RENDER_WORLD,
+        ..Default::default()
+    };
+    let meshes = terrain_to_mesh_with_options(&heightmap, options).expect("Vertices");
+
+    assert_eq!(2, meshes.len());
+    let mut num_triangles = 0;
+    for mesh in &meshes {
+        let Some(Indices::U16(indices)) = mesh.indices() else {
+            panic!("Expected u16 indices.")
+        };
+        assert!(mesh.count_vertices() <= usize::from(u16::MAX));
+        assert_eq!(RenderAssetUsages::RENDER_WORLD, mesh.asset_usage);
+        num_triangles += indices.len() / 3;
+    }
+    assert_eq!(2 * (size - 1) * (size - 1), num_triangles);
+    assert!(terrain_to_mesh(&heightmap).is_ok());
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use bevy::mesh::{Indices, VertexAttributeValues};
+use bevy::prelude::Mesh;
+use bevy::render::render_resource::PrimitiveTopology;
+use common::positions;
+use geo_bevy::*;
+use geo_types::geometry::*;
+
+fn screen_space() -> MeshOptions {
+    MeshOptions {
+        line_style: LineStyle::ScreenSpace,
+        ..Default::default()
+    }
+}
+
+fn extrusions(mesh: &Mesh) -> &[[f32; 2]] {
+    match mesh.attribute(ATTRIBUTE_LINE_EXTRUSION) {
+        Some(VertexAttributeValues::Float32x2(extrusions)) => extrusions,
+        _ => panic!("Expected extrusions."),
+    }
+}
+
+#[test]
+pub fn extrudes_line_strings_with_bevel_joins() {
+    let line_string = LineString::from(vec![[0., 0.], [2., 0.], [2., 2.]]);
+    let mesh = line_string_to_mesh_with_options(line_string, screen_space()).expect("Mesh");
+    assert_eq!(PrimitiveTopology::TriangleList, mesh.primitive_topology());
+
+    let positions = positions(&mesh);
+    // Every vertex stays on the line.
+    assert_eq!(
+        vec![
+            [0., 0., 0.],
+            [0., 0., 0.],
+            [2., 0., 0.],
+            [2., 0., 0.],
+            [2., 0., 0.],
+            [2., 0., 0.],
+            [2., 2., 0.],
+            [2., 2., 0.],
+            [2., 0., 0.],
+        ],
+        *positions
+    );
+    assert_eq!(
+        [
+            [0., 1.],
+            [0., -1.],
+            [0., 1.],
+            [0., -1.],
+            [-1., 0.],
+            [1., 0.],
+            [-1., 0.],
+            [1., 0.],
+            [0., 0.],
+        ],
+        extrusions(&mesh)
+    );
+    assert_eq!(
+        Some(&Indices::U32(vec![
+            0, 1, 3, 0, 3, 2, 4, 5, 7, 4, 7, 6, 8, 2, 4, 8, 3, 5
+        ])),
+        mesh.indices()
+    );
+}
+
+#[test]
+pub fn joins_outlines_at_their_first_vertex() {
+    let outline = rect_to_mesh_with_options(Rect::new([0., 0.], [1., 1.]), screen_space())
+        .expect("Mesh")
+        .exterior_mesh
+        .expect("Outline");
+    // A quad per side and a join per corner.
+    assert_eq!(20, extrusions(&outline).len());
+    assert_eq!(
+        4,
+        extrusions(&outline)
+            .iter()
+            .filter(|extrusion| **extrusion == [0., 0.])
+            .count()
+    );
+    assert_eq!(4 * 6 + 4 * 6, outline.indices().unwrap().len());
+}
+
+#[test]
+pub fn splits_extruded_lines_to_fit_u16_indices() {
+    let options = MeshOptions {
+        index_width: IndexWidth::U16,
+        line_distances: true,
+        ..screen_space()
+    };
+    let mut builder = LineStringMeshBuilder::new().with_options(options);
+    builder
+        .add_line_string(&LineString::from(
+            (0..20_000).map(|x| [x as f64, 0.]).collect::<Vec<_>>(),
+        ))
+        .unwrap();
+    assert_eq!(2, builder.num_meshes());
+    for mesh in builder.build_meshes().expect("Meshes") {
+        let num_vertices = extrusions(&mesh).len();
+        assert!(num_vertices <= usize::from(u16::MAX));
+        let Some(VertexAttributeValues::Float32(distances)) =
+            mesh.attribute(ATTRIBUTE_LINE_DISTANCE)
+        else {
+            panic!("Expected distances.")
+        };
+        assert_eq!(num_vertices, distances.len());
+    }
+}
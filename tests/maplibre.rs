@@ -0,0 +1,309 @@
+#![cfg(feature = "maplibre")]
+
+use bevy::color::LinearRgba;
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::Mesh;
+use geo_bevy::*;
+use geo_types::geometry::*;
+use serde_json::{json, Map, Value as Json};
+
+const STYLE: &str = r##"{
+    "version": 8,
+    "sources": {
+        "parcels": { "type": "geojson", "data": "parcels.geojson" },
+        "roads": { "type": "geojson", "data": "roads.geojson" },
+        "places": { "type": "geojson", "data": "places.geojson" },
+        "satellite": { "type": "raster", "tiles": ["satellite/{z}/{x}/{y}.png"] }
+    },
+    "layers": [
+        { "id": "background", "type": "background", "paint": { "background-color": "#fff" } },
+        {
+            "id": "parcels",
+            "type": "fill",
+            "source": "parcels",
+            "filter": ["==", "$type", "Polygon"],
+            "paint": {
+                "fill-color": ["match", ["get", "zone"], "park", "#00ff00", ["road", "rail"], "gray", "rgb(255, 0, 0)"],
+                "fill-opacity": 0.5
+            }
+        },
+        {
+            "id": "roads",
+            "type": "line",
+            "source": "roads",
+            "minzoom": 8,
+            "filter": ["all", ["in", ["get", "class"], ["literal", ["primary", "secondary"]]], ["!=", ["get", "tunnel"], true]],
+            "paint": {
+                "line-color": "hsl(0, 100%, 50%)",
+                "line-width": ["interpolate", ["linear"], ["zoom"], 10, 1, 14, 5],
+                "line-dasharray": [2, 1]
+            }
+        },
+        { "id": "labels", "type": "symbol", "source": "places" },
+        {
+            "id": "places",
+            "type": "circle",
+            "source": "places",
+            "layout": { "visibility": "none" },
+            "paint": { "circle-radius": { "stops": [[0, 2], [10, 6]] } }
+        }
+    ]
+}"##;
+
+fn properties(properties: Json) -> Map<String, Json> {
+    match properties {
+        Json::Object(properties) => properties,
+        _ => panic!("Expected an object."),
+    }
+}
+
+#[test]
+pub fn parses_layers_and_sources() {
+    let style = MapStyle::from_json(STYLE).expect("Style");
+    assert_eq!(
+        vec![
+            ("parcels", LayerKind::Fill, 1),
+            ("roads", LayerKind::Line, 2),
+            ("places", LayerKind::Circle, 4),
+        ],
+        style
+            .layers
+            .iter()
+            .map(|layer| (layer.id.as_str(), layer.kind, layer.index))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["parcels", "places", "roads"],
+        style.sources.keys().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Source::GeoJson {
+            data: "parcels.geojson".into()
+        },
+        style.sources["parcels"]
+    );
+    assert_eq!(
+        vec!["roads"],
+        style
+            .layers_for("roads")
+            .map(|layer| layer.id.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert!(!style.layers[2].visible);
+
+    assert!(matches!(
+        MapStyle::from_json(
+            r#"{ "layers": [{ "id": "a", "type": "line", "source": "b",
+            "paint": { "line-width": ["sqrt", 4] } }] }"#
+        ),
+        Err(Error::InvalidStyle(_))
+    ));
+    assert!(matches!(MapStyle::from_json("{"), Err(Error::Json(_))));
+    // Vector tiles aren't decoded.
+    assert!(matches!(
+        MapStyle::from_json(
+            r#"{ "sources": { "base": { "type": "vector", "tiles": ["{z}/{x}/{y}.mvt"] } },
+            "layers": [{ "id": "a", "type": "line", "source": "base", "source-layer": "roads" }] }"#
+        ),
+        Err(Error::UnsupportedSource(source)) if source == "base"
+    ));
+}
+
+#[test]
+pub fn filters_features_by_geometry_properties_and_zoom() {
+    let style = MapStyle::from_json(STYLE).expect("Style");
+    let [parcels, roads, places] = &style.layers[..] else {
+        panic!("Expected three layers.")
+    };
+    let road: Geometry = LineString::from(vec![[0., 0.], [1., 0.]]).into();
+    let primary = properties(json!({ "class": "primary" }));
+    let tunnel = properties(json!({ "class": "primary", "tunnel": true }));
+    let path = properties(json!({ "class": "path" }));
+
+    assert!(roads.matches(&road, &primary, 12.));
+    assert!(!roads.matches(&road, &primary, 6.));
+    assert!(!roads.matches(&road, &tunnel, 12.));
+    assert!(!roads.matches(&road, &path, 12.));
+    // Line layers draw polygon outlines, fill layers only polygons.
+    let parcel: Geometry = Rect::new([0., 0.], [1., 1.]).into();
+    assert!(roads.matches(&parcel, &primary, 12.));
+    assert!(parcels.matches(&parcel, &Map::new(), 0.));
+    assert!(!parcels.matches(&road, &Map::new(), 0.));
+    // Hidden layers draw nothing.
+    assert!(!places.matches(&Geometry::Point(Point::new(0., 0.)), &Map::new(), 0.));
+}
+
+#[test]
+pub fn styles_and_meshes_layers_at_a_zoom() {
+    let style = MapStyle::from_json(STYLE).expect("Style");
+    let [parcels, roads, places] = &style.layers[..] else {
+        panic!("Expected three layers.")
+    };
+
+    let places = places.style(5.);
+    assert_eq!(Some(8.), places.point_size.evaluate(&Map::new()));
+
+    let roads_options = roads.mesh_options();
+    assert_eq!(LineStyle::ScreenSpace, roads_options.line_style);
+    // Dashed by the material, in line widths.
+    assert!(roads_options.line_distances && roads_options.dash.is_none());
+    #[cfg(feature = "material")]
+    {
+        let material = roads
+            .line_material()
+            .expect("Material")
+            .expect("Line layer");
+        assert!(material.vertex_styles);
+        assert_eq!(
+            (3., [2., 1., 0., 0.]),
+            (
+                material.line.dash_period,
+                material.line.dash_lengths[0].to_array()
+            )
+        );
+        assert!(parcels.line_material().expect("No material").is_none());
+    }
+    let roads_style = roads.style(12.).resolve(&Map::new());
+    assert_eq!(
+        (LinearRgba::RED, 3., 2.),
+        (
+            roads_style.stroke_color,
+            roads_style.stroke_width,
+            roads_style.z_order
+        )
+    );
+    let style = MapStyle::from_json(STYLE).expect("Style").with_z_step(0.25);
+    assert_eq!(0.5, style.layers[1].style(12.).resolve(&Map::new()).z_order);
+
+    let features = [
+        (
+            Geometry::from(Rect::new([0., 0.], [1., 1.])),
+            properties(json!({ "zone": "park" })),
+        ),
+        (
+            Geometry::from(Rect::new([1., 0.], [2., 1.])),
+            properties(json!({ "zone": "housing" })),
+        ),
+        (
+            Geometry::from(Point::new(0., 0.)),
+            properties(json!({ "zone": "park" })),
+        ),
+    ];
+    let meshes = parcels.build_meshes(features, 12.).expect("Meshes");
+    let [GeometryMesh::PolygonParts(mesh)] = &meshes[..] else {
+        panic!("Expected one polygon mesh.")
+    };
+    assert!(mesh.exterior_mesh.is_none());
+    let Some(VertexAttributeValues::Float32x4(colors)) = mesh
+        .mesh
+        .as_ref()
+        .expect("Fill")
+        .attribute(Mesh::ATTRIBUTE_COLOR)
+    else {
+        panic!("Expected colors.")
+    };
+    assert_eq!(
+        [[0., 1., 0., 0.5]; 4]
+            .into_iter()
+            .chain([[1., 0., 0., 0.5]; 4])
+            .collect::<Vec<_>>(),
+        *colors
+    );
+    assert!(parcels
+        .build_meshes(Vec::<(Geometry, Map<String, Json>)>::new(), 12.)
+        .expect("No meshes")
+        .is_empty());
+}
+
+#[test]
+pub fn meshes_polygon_outlines_in_line_layers() {
+    let style = MapStyle::from_json(STYLE).expect("Style");
+    let roads = &style.layers[1];
+    let primary = properties(json!({ "class": "primary" }));
+    let features = [
+        (
+            Geometry::from(Rect::new([0., 0.], [1., 1.])),
+            primary.clone(),
+        ),
+        (
+            Geometry::from(MultiPolygon::new(vec![
+                Rect::new([2., 0.], [3., 1.]).to_polygon(),
+                Rect::new([4., 0.], [5., 1.]).to_polygon(),
+            ])),
+            primary,
+        ),
+    ];
+    let meshes = roads.build_meshes(features, 12.).expect("Meshes");
+    let [GeometryMesh::PolygonParts(mesh)] = &meshes[..] else {
+        panic!("Expected one polygon mesh.")
+    };
+    assert!(mesh.mesh.is_none());
+    let Some(VertexAttributeValues::Float32x3(positions)) = mesh
+        .exterior_mesh
+        .as_ref()
+        .expect("Outlines")
+        .attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("Expected positions.")
+    };
+    // The outlines of all three polygons.
+    for x in [0., 1., 2., 3., 4., 5.] {
+        assert!(positions.iter().any(|position| position[0] == x));
+    }
+}
+
+#[test]
+pub fn loads_inline_geojson_sources() {
+    let style = MapStyle::from_json(
+        r#"{
+            "sources": {
+                "inline": { "type": "geojson", "data": {
+                    "type": "FeatureCollection",
+                    "features": [
+                        { "type": "Feature", "properties": { "zone": "park" }, "geometry": {
+                            "type": "Polygon",
+                            "coordinates": [[[0, 0], [4, 0], [4, 4], [0, 0]], [[1, 0.5], [3, 0.5], [3, 2.5], [1, 0.5]]]
+                        } },
+                        { "type": "Feature", "properties": null, "geometry": {
+                            "type": "MultiLineString", "coordinates": [[[0, 0, 10], [1, 1, 10]]]
+                        } },
+                        { "type": "Feature", "properties": {}, "geometry": null }
+                    ]
+                } },
+                "remote": { "type": "geojson", "data": "parcels.geojson" }
+            },
+            "layers": [{ "id": "fill", "type": "fill", "source": "inline" }]
+        }"#,
+    )
+    .expect("Style");
+    assert!(style.sources["remote"].features().is_none());
+    let features = style.sources["inline"]
+        .features()
+        .expect("Inline data")
+        .expect("Features");
+    assert_eq!(2, features.len());
+    let Geometry::Polygon(polygon) = &features[0].0 else {
+        panic!("Expected a polygon.")
+    };
+    assert_eq!(1, polygon.interiors().len());
+    assert_eq!(Some(&json!("park")), features[0].1.get("zone"));
+    assert_eq!(
+        Geometry::from(MultiLineString::new(vec![vec![[0., 0.], [1., 1.]].into()])),
+        features[1].0
+    );
+    let meshes = style.layers[0].build_meshes(features, 0.).expect("Meshes");
+    assert!(matches!(&meshes[..], [GeometryMesh::PolygonParts(_)]));
+
+    assert!(matches!(
+        parse_geojson(
+            &json!({ "type": "Feature", "geometry": { "type": "Point", "coordinates": [1] } })
+        ),
+        Err(Error::InvalidGeoJson(_))
+    ));
+    assert_eq!(
+        1,
+        parse_geojson(&json!({ "type": "Point", "coordinates": [1, 2] }))
+            .expect("Geometry")
+            .len()
+    );
+}
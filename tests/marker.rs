@@ -0,0 +1,99 @@
+mod common;
+
+use bevy::mesh::{Indices, VertexAttributeValues};
+use bevy::prelude::Mesh;
+use common::positions;
+use geo_bevy::*;
+use geo_types::geometry::*;
+
+#[test]
+pub fn places_markers_along_line_strings() {
+    let mut builder =
+        MarkerMeshBuilder::new(Markers::new(MarkerShape::Chevron, 2., 1.)).expect("Markers");
+    builder
+        .add_line_string(&LineString::from(vec![[0., 0.], [4., 0.], [4., 4.]]))
+        .unwrap();
+    assert_eq!(4, builder.len());
+
+    let mesh = Mesh::try_from(builder).expect("Markers");
+    let positions = positions(&mesh);
+    assert_eq!(24, positions.len());
+    // The tip of each chevron points along its segment.
+    let tips = positions
+        .chunks_exact(6)
+        .map(|chevron| [chevron[0][0], chevron[0][1]])
+        .collect::<Vec<_>>();
+    assert_eq!(vec![[1.5, 0.], [3.5, 0.], [4., 1.5], [4., 3.5]], tips);
+
+    let Some(VertexAttributeValues::Float32x3(normals)) = mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+    else {
+        panic!("Expected normals.")
+    };
+    assert!(normals.iter().all(|normal| *normal == [0., 0., 1.]));
+}
+
+#[test]
+pub fn starts_markers_at_offset() {
+    let markers = Markers::new(MarkerShape::Arrow, 3., 0.5).with_offset(0.);
+    let mut builder = MarkerMeshBuilder::new(markers)
+        .expect("Markers")
+        .with_options(MeshOptions {
+            z_offset: 1.,
+            ..Default::default()
+        });
+    builder.add_line(&Line::new([0., 0.], [0., 6.])).unwrap();
+    assert_eq!(3, builder.len());
+    let mesh = Mesh::try_from(builder).expect("Markers");
+    assert_eq!(21, positions(&mesh).len());
+    assert!(positions(&mesh).iter().all(|[_, _, z]| *z == 1.));
+    // The arrow head points up the line from the start.
+    assert_eq!([0., 0.25, 1.], positions(&mesh)[5]);
+
+    assert!(matches!(
+        MarkerMeshBuilder::new(Markers::new(MarkerShape::Arrow, 0., 1.)),
+        Err(Error::InvalidMarkers(_))
+    ));
+    // Line strings that would take the builder past its cap add nothing.
+    let mut builder =
+        MarkerMeshBuilder::new(Markers::new(MarkerShape::Arrow, 1., 1.).with_max_markers(4))
+            .expect("Markers");
+    builder.add_line(&Line::new([0., 0.], [0., 3.])).unwrap();
+    assert!(matches!(
+        builder.add_line(&Line::new([0., 0.], [0., 2.])),
+        Err(Error::InvalidMarkers(_))
+    ));
+    assert_eq!(3, builder.len());
+}
+
+#[test]
+pub fn splits_markers_to_fit_u16_indices() {
+    let mut builder = MarkerMeshBuilder::new(Markers::new(MarkerShape::Arrow, 1., 0.5))
+        .expect("Markers")
+        .with_options(MeshOptions {
+            index_width: IndexWidth::U16,
+            ..Default::default()
+        });
+    builder
+        .add_line(&Line::new([0., 0.], [10_000., 0.]))
+        .unwrap();
+    assert_eq!(10_000, builder.len());
+    assert_eq!(2, builder.num_meshes());
+
+    let meshes = builder.build_meshes().expect("Meshes");
+    assert_eq!(
+        70_000,
+        meshes
+            .iter()
+            .map(|mesh| positions(mesh).len())
+            .sum::<usize>()
+    );
+    for mesh in &meshes {
+        let Some(Indices::U16(indices)) = mesh.indices() else {
+            panic!("Expected u16 indices.")
+        };
+        assert!(indices
+            .iter()
+            .all(|index| usize::from(*index) < positions(mesh).len()));
+    }
+    assert!(matches!(Mesh::try_from(builder), Err(Error::IndexOverflow)));
+}
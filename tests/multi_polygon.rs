@@ -0,0 +1,62 @@
+mod common;
+
+use bevy::mesh::Indices;
+use common::square_with_hole;
+use geo_bevy::*;
+use geo_types::geometry::*;
+
+fn islands() -> MultiPolygon {
+    MultiPolygon::new(vec![
+        square_with_hole(4., 1., 3.),
+        Polygon::new(vec![[5., 0.], [6., 0.], [6., 1.]].into(), vec![]),
+    ])
+}
+
+#[test]
+pub fn merges_polygons_with_index_ranges() {
+    let MultiPolygonMesh {
+        mesh,
+        outline_mesh,
+        ranges,
+    } = multi_polygon_to_merged_mesh(islands()).expect("Meshes");
+    let (mesh, outline_mesh) = (mesh.expect("Fill"), outline_mesh.expect("Outline"));
+    assert_eq!(8 + 3, mesh.count_vertices());
+    assert_eq!(8 + 3, outline_mesh.count_vertices());
+
+    assert_eq!(
+        vec![
+            PolygonIndexRanges {
+                fill: 0..24,
+                outline: 0..16,
+            },
+            PolygonIndexRanges {
+                fill: 24..27,
+                outline: 16..22,
+            },
+        ],
+        ranges
+    );
+    assert_eq!(27, mesh.indices().expect("Indices").len());
+    assert_eq!(22, outline_mesh.indices().expect("Indices").len());
+}
+
+#[test]
+pub fn derives_meshes_of_single_polygons() {
+    let multi_polygon_mesh = multi_polygon_to_merged_mesh(islands()).expect("Meshes");
+    let PolygonMeshParts {
+        mesh, outline_mesh, ..
+    } = multi_polygon_mesh.part(1).expect("Second polygon");
+    let Some(Indices::U32(indices)) = mesh.as_ref().expect("Fill").indices().cloned() else {
+        panic!("Expected u32 indices.")
+    };
+    assert!(indices.iter().all(|index| *index >= 8));
+    assert_eq!(
+        6,
+        outline_mesh
+            .expect("Outline")
+            .indices()
+            .expect("Indices")
+            .len()
+    );
+    assert!(multi_polygon_mesh.part(2).is_none());
+}
@@ -0,0 +1,354 @@
+mod common;
+
+use bevy::mesh::{Indices, VertexAttributeValues};
+use bevy::render::render_resource::PrimitiveTopology;
+use common::{positions, square_with_hole};
+use geo_bevy::*;
+use geo_types::geometry::*;
+
+#[test]
+pub fn builds_line_strips_with_u16_indices() {
+    let options = MeshOptions {
+        topology: PrimitiveTopology::LineStrip,
+        index_width: IndexWidth::U16,
+        normals: false,
+        ..Default::default()
+    };
+    let mesh = multi_line_string_to_mesh_with_options(
+        MultiLineString::new(vec![
+            vec![[0., 0.], [1., 0.], [1., 1.]].into(),
+            vec![[2., 0.], [3., 0.]].into(),
+        ]),
+        options.clone(),
+    )
+    .expect("Meshes");
+    assert_eq!(PrimitiveTopology::LineStrip, mesh[0].primitive_topology());
+    assert_eq!(Some(&Indices::U16(vec![0, 1, 2])), mesh[0].indices());
+    assert!(mesh[0]
+        .attribute(bevy::prelude::Mesh::ATTRIBUTE_NORMAL)
+        .is_none());
+
+    let Ok(GeometryMesh::LineString(mesh)) = geometry_to_mesh_with_options(
+        Geometry::MultiLineString(MultiLineString::new(vec![
+            vec![[0., 0.], [1., 0.]].into(),
+            vec![[2., 0.], [3., 0.]].into(),
+        ])),
+        options,
+    ) else {
+        panic!("Expected a line string mesh.")
+    };
+    // Strips are separated by a primitive restart index.
+    assert_eq!(
+        Some(&Indices::U16(vec![0, 1, u16::MAX, 2, 3])),
+        mesh.indices()
+    );
+}
+
+#[test]
+pub fn offsets_fills_and_skips_outlines() {
+    let PolygonMeshParts {
+        mesh,
+        exterior_mesh,
+        interior_meshes,
+        ..
+    } = polygon_to_mesh_with_options(
+        Polygon::new(vec![[0., 0.], [2., 0.], [2., 2.], [0., 2.]].into(), vec![]),
+        MeshOptions {
+            z_offset: 0.5,
+            polygon_mode: PolygonMode::Fill,
+            ..Default::default()
+        },
+    )
+    .expect("Vertices");
+    let mesh = mesh.expect("Fill");
+    assert!(exterior_mesh.is_none());
+    assert!(interior_meshes.is_empty());
+    assert!(positions(&mesh).iter().all(|[_, _, z]| *z == 0.5));
+
+    let Some(VertexAttributeValues::Float32x3(normals)) =
+        mesh.attribute(bevy::prelude::Mesh::ATTRIBUTE_NORMAL)
+    else {
+        panic!("Expected normals.")
+    };
+    assert!(normals.iter().all(|normal| *normal == [0., 0., 1.]));
+    let Some(VertexAttributeValues::Float32x2(uvs)) =
+        mesh.attribute(bevy::prelude::Mesh::ATTRIBUTE_UV_0)
+    else {
+        panic!("Expected UVs.")
+    };
+    assert!(uvs.contains(&[2., 2.]));
+}
+
+#[test]
+pub fn rejects_unsupported_options() {
+    let line_string = LineString::from(vec![[0., 0.], [1., 0.]]);
+    assert!(matches!(
+        line_string_to_mesh_with_options(
+            line_string,
+            MeshOptions {
+                topology: PrimitiveTopology::TriangleList,
+                ..Default::default()
+            }
+        ),
+        Err(Error::UnsupportedTopology(PrimitiveTopology::TriangleList))
+    ));
+
+    let line_string = LineString::from(
+        (0..=u16::MAX)
+            .map(|i| [f64::from(i), 0.])
+            .collect::<Vec<_>>(),
+    );
+    assert!(matches!(
+        line_string_to_mesh_with_options(
+            line_string,
+            MeshOptions {
+                index_width: IndexWidth::U16,
+                ..Default::default()
+            }
+        ),
+        Err(Error::IndexOverflow)
+    ));
+}
+
+#[test]
+pub fn combines_outlines_without_fill() {
+    let PolygonMeshParts {
+        mesh,
+        exterior_mesh,
+        interior_meshes,
+        outline_mesh,
+    } = polygon_to_mesh_with_options(
+        square_with_hole(4., 1., 3.),
+        MeshOptions {
+            polygon_mode: PolygonMode::Outline,
+            outline_grouping: OutlineGrouping::Combined,
+            ..Default::default()
+        },
+    )
+    .expect("Vertices");
+    assert!(mesh.is_none());
+    assert!(exterior_mesh.is_none());
+    assert!(interior_meshes.is_empty());
+
+    let outline_mesh = outline_mesh.expect("Outline");
+    assert_eq!(8, outline_mesh.count_vertices());
+    // Both rings, without a segment joining them.
+    assert_eq!(16, outline_mesh.indices().expect("Indices").len());
+}
+
+#[test]
+pub fn splits_line_strings_at_u16_limit() {
+    let mut builder = LineStringMeshBuilder::new().with_options(MeshOptions {
+        topology: PrimitiveTopology::LineStrip,
+        index_width: IndexWidth::U16,
+        ..Default::default()
+    });
+    builder
+        .add_line_string(&LineString::from(
+            (0..100_000).map(|i| [f64::from(i), 0.]).collect::<Vec<_>>(),
+        ))
+        .expect("Vertices");
+    assert_eq!(2, builder.num_meshes());
+
+    let meshes = builder.build_meshes().expect("Meshes");
+    assert_eq!(usize::from(u16::MAX), meshes[0].count_vertices());
+    // The second mesh picks up from the last vertex of the first.
+    assert_eq!(100_000 - 65_535 + 1, meshes[1].count_vertices());
+    assert_eq!([65_534., 0., 0.], positions(&meshes[1])[0]);
+    let Some(Indices::U16(indices)) = meshes[1].indices() else {
+        panic!("Expected u16 indices.")
+    };
+    assert_eq!([0, 1, 2], indices[0..3]);
+}
+
+#[test]
+pub fn splits_fills_at_u16_limit() {
+    // A 300 × 300 grid of squares is too large for a single u16 mesh.
+    let squares = MultiPolygon::new(
+        (0..300 * 300)
+            .map(|i| {
+                Rect::new(
+                    [f64::from(i % 300), f64::from(i / 300)],
+                    [f64::from(i % 300) + 0.5, f64::from(i / 300) + 0.5],
+                )
+                .to_polygon()
+            })
+            .collect(),
+    );
+    let options = MeshOptions {
+        index_width: IndexWidth::U16,
+        polygon_mode: PolygonMode::Fill,
+        ..Default::default()
+    };
+    assert!(matches!(
+        geometry_to_mesh_with_options(Geometry::MultiPolygon(squares.clone()), options.clone()),
+        Err(Error::CouldNotBuildMesh)
+    ));
+
+    let meshes =
+        geometry_to_meshes_with_options(Geometry::MultiPolygon(squares), options).expect("Meshes");
+    let fills = meshes
+        .iter()
+        .map(|mesh| match mesh {
+            GeometryMesh::Polygon(PolygonMesh { mesh: fill, .. })
+            | GeometryMesh::PolygonParts(PolygonMeshParts {
+                mesh: Some(fill), ..
+            }) => fill,
+            _ => panic!("Expected a polygon fill."),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(6, fills.len());
+    assert!(fills
+        .iter()
+        .all(|fill| fill.count_vertices() <= usize::from(u16::MAX)));
+    // Squares are never split across meshes.
+    assert_eq!(
+        300 * 300 * 6,
+        fills
+            .iter()
+            .map(|fill| fill.indices().expect("Indices").len())
+            .sum::<usize>()
+    );
+}
+
+#[test]
+pub fn builds_outlines_in_every_polygon_mode() {
+    let options = |polygon_mode| MeshOptions {
+        polygon_mode,
+        ..Default::default()
+    };
+    let polygon = || Geometry::Polygon(square_with_hole(4., 1., 3.));
+
+    let meshes = geometry_to_meshes_with_options(polygon(), options(PolygonMode::Outline))
+        .expect("Outlines");
+    let [GeometryMesh::PolygonParts(PolygonMeshParts {
+        mesh: None,
+        exterior_mesh: Some(_),
+        interior_meshes,
+        ..
+    })] = &meshes[..]
+    else {
+        panic!("Expected polygon outlines without a fill.")
+    };
+    assert_eq!(1, interior_meshes.len());
+
+    let meshes = geometry_to_meshes_with_options(polygon(), options(PolygonMode::FillAndOutline))
+        .expect("Meshes");
+    assert!(matches!(&meshes[..], [GeometryMesh::Polygon(_)]));
+}
+
+#[test]
+pub fn merges_line_strings_into_one_mesh() {
+    let roads = || {
+        MultiLineString::new(
+            (0..1000)
+                .map(|i| vec![[f64::from(i), 0.], [f64::from(i), 1.], [f64::from(i), 2.]].into())
+                .collect(),
+        )
+    };
+
+    let meshes = multi_line_string_to_mesh_with_options(
+        roads(),
+        MeshOptions {
+            merge_line_strings: true,
+            ..Default::default()
+        },
+    )
+    .expect("Meshes");
+    assert_eq!(1, meshes.len());
+    assert_eq!(PrimitiveTopology::LineList, meshes[0].primitive_topology());
+    assert_eq!(3000, meshes[0].count_vertices());
+    assert_eq!(4000, meshes[0].indices().expect("Indices").len());
+
+    let meshes = multi_line_string_to_mesh_with_options(
+        roads(),
+        MeshOptions {
+            topology: PrimitiveTopology::LineStrip,
+            merge_line_strings: true,
+            ..Default::default()
+        },
+    )
+    .expect("Meshes");
+    assert_eq!(1, meshes.len());
+    // One index per vertex, plus a strip cut between line strings.
+    assert_eq!(3000 + 999, meshes[0].indices().expect("Indices").len());
+
+    let meshes = multi_line_string_to_mesh_with_options(
+        roads(),
+        MeshOptions {
+            topology: PrimitiveTopology::LineStrip,
+            index_width: IndexWidth::U16,
+            merge_line_strings: true,
+            ..Default::default()
+        },
+    )
+    .expect("Meshes");
+    let Some(Indices::U16(indices)) = meshes[0].indices() else {
+        panic!("Expected u16 indices.")
+    };
+    // Narrowed indices cut strips with the u16 restart value.
+    assert_eq!([0, 1, 2, u16::MAX, 3, 4, 5, u16::MAX], indices[..8]);
+    assert_eq!(
+        999,
+        indices.iter().filter(|index| **index == u16::MAX).count()
+    );
+}
+
+#[test]
+pub fn closes_rings_the_same_way_from_every_entry_point() {
+    let outline = |polygon_mesh: PolygonMesh| {
+        let mesh = polygon_mesh.exterior_mesh;
+        (positions(&mesh).to_vec(), mesh.indices().cloned())
+    };
+    let rect = Rect::new([0., 0.], [1., 1.]);
+    let Ok(GeometryMesh::Polygon(from_geometry)) = geometry_to_mesh(Geometry::Rect(rect)) else {
+        panic!("Expected a polygon mesh.")
+    };
+    let from_rect = rect_to_mesh(rect).expect("Vertices");
+    let (positions, indices) = outline(from_rect);
+    assert_eq!((positions.clone(), indices.clone()), outline(from_geometry));
+    assert_eq!(4, positions.len());
+    assert_eq!(Some(Indices::U32(vec![0, 1, 1, 2, 2, 3, 3, 0])), indices);
+
+    // Open and closed rings give the same meshes.
+    let open = LineString::from(vec![[0., 0.], [1., 0.], [1., 1.]]);
+    let mut builder = PolygonMeshBuilder::<f64>::new();
+    builder
+        .add_polygon_from_exterior_coords(open.coords().copied())
+        .expect("Vertices");
+    builder
+        .add_polygon(&Polygon::new(open, vec![]))
+        .expect("Vertices");
+    let PolygonMesh {
+        mesh,
+        exterior_mesh,
+        ..
+    } = PolygonMesh::try_from(builder).expect("Vertices");
+    assert_eq!(6, mesh.count_vertices());
+    assert_eq!(6, exterior_mesh.count_vertices());
+}
+
+#[test]
+pub fn closes_line_strip_outlines() {
+    let triangle = Triangle::new([0., 0.].into(), [1., 0.].into(), [1., 1.].into());
+    let outline = |ring_closure| {
+        let options = MeshOptions {
+            topology: PrimitiveTopology::LineStrip,
+            ring_closure,
+            ..Default::default()
+        };
+        let mesh = triangle_to_mesh_with_options(triangle, options)
+            .expect("Vertices")
+            .exterior_mesh
+            .expect("Outline");
+        (mesh.count_vertices(), mesh.indices().cloned())
+    };
+    assert_eq!(
+        (3, Some(Indices::U32(vec![0, 1, 2, 0]))),
+        outline(RingClosure::Indexed)
+    );
+    assert_eq!(
+        (4, Some(Indices::U32(vec![0, 1, 2, 3]))),
+        outline(RingClosure::Duplicated)
+    );
+}
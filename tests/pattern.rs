@@ -0,0 +1,104 @@
+mod common;
+
+use bevy::mesh::{Indices, VertexAttributeValues};
+use bevy::prelude::Mesh;
+use common::{positions, square_with_hole};
+use geo_bevy::*;
+use geo_types::geometry::*;
+use std::f64::consts::FRAC_PI_4;
+
+fn fill(polygon: Polygon, pattern: FillPattern) -> Mesh {
+    let options = MeshOptions {
+        polygon_mode: PolygonMode::Fill,
+        fill_pattern: Some(pattern),
+        z_offset: 2.,
+        ..Default::default()
+    };
+    let mesh = polygon_to_mesh_with_options(polygon, options).expect("Mesh");
+    mesh.mesh.expect("Fill")
+}
+
+/// Total area of the mesh's triangles, negative where they face down.
+fn area(mesh: &Mesh) -> f32 {
+    let Some(Indices::U32(indices)) = mesh.indices() else {
+        panic!("Expected indices.")
+    };
+    let positions = positions(mesh);
+    indices
+        .chunks_exact(3)
+        .map(|triangle| {
+            let [a, b, c] = [0, 1, 2].map(|corner| positions[triangle[corner] as usize]);
+            ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])) / 2.
+        })
+        .sum()
+}
+
+#[test]
+pub fn clips_hatches_to_polygons_and_holes() {
+    let mesh = fill(
+        square_with_hole(10., 4., 6.),
+        FillPattern::hatch(0., 2., 1.),
+    );
+    // Bands around y = 0, 2, …, 10, halved at the edges, less 1 by 2 of the hole.
+    assert!((area(&mesh) - 48.).abs() < 1e-3);
+    for [x, y, z] in positions(&mesh) {
+        assert!((0. ..=10.).contains(x) && (0. ..=10.).contains(y));
+        assert!(!((4.01..5.99).contains(x) && (4.01..5.99).contains(y)));
+        assert_eq!(2., *z);
+        assert!((y + 0.5).rem_euclid(2.) <= 1.001);
+    }
+
+    // Crossings are covered once: all but the 5 by 5 between bands, less 3 of the hole.
+    let cross_hatched = fill(
+        square_with_hole(10., 4., 6.),
+        FillPattern::cross_hatch(0., 2., 1.),
+    );
+    assert!((area(&cross_hatched) - 72.).abs() < 1e-3);
+    let rotated = fill(
+        square_with_hole(10., 4., 6.),
+        FillPattern::cross_hatch(FRAC_PI_4, 2., 1.),
+    );
+    assert!((area(&rotated) - 72.).abs() < 1.);
+    let solid = fill(
+        square_with_hole(10., 4., 6.),
+        FillPattern::cross_hatch(0., 2., 2.),
+    );
+    assert!((area(&solid) - 96.).abs() < 1e-3);
+}
+
+#[test]
+pub fn clips_dots_to_polygons() {
+    let mesh = fill(
+        Rect::new([0., 0.], [10., 10.]).to_polygon(),
+        FillPattern::dots(0., 5., 1.),
+    );
+    // One whole dot, four halves and four quarters of octagons with an inradius of 0.5.
+    let octagon = 8. * 0.25 * (FRAC_PI_4 / 2.).tan() as f32;
+    assert!((area(&mesh) - 4. * octagon).abs() < 1e-3);
+    assert!(positions(&mesh)
+        .iter()
+        .all(|[x, y, _]| (-1e-4..=10.0001).contains(x) && (-1e-4..=10.0001).contains(y)));
+}
+
+#[test]
+pub fn keeps_fills_facing_up() {
+    let triangle = Polygon::new(vec![[0., 0.], [0., 10.], [10., 0.]].into(), vec![]);
+    let mesh = fill(triangle.clone(), FillPattern::hatch(FRAC_PI_4, 1., 0.25));
+    assert!(area(&mesh) > 0.);
+    let Some(VertexAttributeValues::Float32x3(normals)) = mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+    else {
+        panic!("Expected normals.")
+    };
+    assert!(normals.iter().all(|[_, _, z]| *z > 0.999));
+
+    // Invalid patterns fail rather than leave fills solid.
+    let options = MeshOptions {
+        polygon_mode: PolygonMode::Fill,
+        fill_pattern: Some(FillPattern::hatch(0., 0., 1.)),
+        ..Default::default()
+    };
+    assert!(matches!(
+        polygon_to_mesh_with_options(triangle, options),
+        Err(Error::InvalidFillPattern(_))
+    ));
+}
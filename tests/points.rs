@@ -0,0 +1,119 @@
+use bevy::color::LinearRgba;
+use bevy::math::{Vec2, Vec3};
+use bevy::transform::components::Transform;
+use geo_bevy::*;
+use geo_traits::{CoordTrait, Dimensions};
+use geo_types::geometry::*;
+
+struct Coord3([f64; 3]);
+
+impl CoordTrait for Coord3 {
+    type T = f64;
+
+    fn dim(&self) -> Dimensions {
+        Dimensions::Xyz
+    }
+
+    fn x(&self) -> f64 {
+        self.0[0]
+    }
+
+    fn y(&self) -> f64 {
+        self.0[1]
+    }
+
+    fn nth_or_panic(&self, n: usize) -> f64 {
+        self.0[n]
+    }
+}
+
+#[test]
+pub fn tags_points_with_their_feature() {
+    let mut ctx = BuildBevyMeshesContext::<f64>::new();
+    ctx.add_geometry(&Geometry::Point(Point::new(0., 0.)))
+        .unwrap();
+    ctx.add_geometry(&Geometry::GeometryCollection(GeometryCollection::new_from(
+        vec![
+            Geometry::MultiPoint(MultiPoint::from(vec![(1., 0.), (2., 0.)])),
+            Geometry::LineString(vec![[0., 0.], [1., 1.]].into()),
+            Geometry::Point(Point::new(3., 0.)),
+        ],
+    )))
+    .unwrap();
+    ctx.add_geometry(&Geometry::Point(Point::new(4., 0.)))
+        .unwrap();
+
+    let Ok(GeometryMesh::Point(points)) = ctx.build_ref() else {
+        panic!("Expected points.")
+    };
+    assert_eq!(
+        vec![(0., 0), (1., 1), (2., 1), (3., 3), (4., 4)],
+        points
+            .iter()
+            .map(|point| (point.x, point.feature))
+            .collect::<Vec<_>>()
+    );
+
+    ctx.clear();
+    ctx.add_geometry(&Geometry::Point(Point::new(0., 0.)))
+        .unwrap();
+    assert!(matches!(
+        ctx.build(),
+        Ok(GeometryMesh::Point(points)) if points[0].feature == 0
+    ));
+}
+
+#[test]
+pub fn converts_styled_points() {
+    let mut builder = PointMeshBuilder::new();
+    builder.add_coord(Coord3([1., 2., 3.]));
+    builder.add_point(&Point::new(4., 5.));
+    builder.points_mut()[0].size = Some(2.);
+    builder.points_mut()[0].rotation = Some(std::f32::consts::FRAC_PI_2);
+    builder.points_mut()[0].color = Some(LinearRgba::BLUE);
+
+    let Ok(GeometryMesh::Point(points)) = builder.build_ref() else {
+        panic!("Expected points.")
+    };
+    assert_eq!(Vec3::new(1., 2., 3.), Vec3::from(points[0]));
+    assert_eq!(Vec2::new(4., 5.), Vec2::from(points[1]));
+    assert_eq!(Vec3::new(4., 5., 0.), Vec3::from(points[1]));
+
+    let transform = Transform::from(points[0]);
+    assert_eq!(Vec3::splat(2.), transform.scale);
+    assert!((transform.rotation * Vec3::X).abs_diff_eq(Vec3::Y, 1e-6));
+    assert_eq!(Transform::from_xyz(4., 5., 0.), Transform::from(points[1]));
+
+    // Points' own styling wins over the layer's.
+    let instances = builder.instances(1., LinearRgba::RED);
+    assert_eq!(
+        (2., [0., 0., 1., 1.]),
+        (instances[0].size, instances[0].color)
+    );
+    assert_eq!(
+        (1., [1., 0., 0., 1.], 0.),
+        (instances[1].size, instances[1].color, instances[1].rotation)
+    );
+}
+
+#[test]
+pub fn offsets_points() {
+    let options = MeshOptions {
+        z_offset: 2.5,
+        ..Default::default()
+    };
+    let Ok(GeometryMesh::Point(points)) =
+        geometry_to_mesh_with_options(Geometry::Point(Point::new(1., 2.)), options.clone())
+    else {
+        panic!("Expected points.")
+    };
+    assert_eq!(Vec3::new(1., 2., 2.5), Vec3::from(points[0]));
+
+    // On top of the coordinate's own z.
+    let mut builder = PointMeshBuilder::new().with_options(options);
+    builder.add_coord(Coord3([1., 2., 3.]));
+    let Ok(GeometryMesh::Point(points)) = builder.build() else {
+        panic!("Expected points.")
+    };
+    assert_eq!(5.5, points[0].z);
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use bevy::color::LinearRgba;
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::Mesh;
+use common::positions;
+use geo_bevy::*;
+use geo_types::geometry::*;
+use std::collections::HashMap;
+
+fn properties<const N: usize>(properties: [(&str, Value); N]) -> HashMap<String, Value> {
+    properties
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value))
+        .collect()
+}
+
+fn colors(mesh: &Mesh) -> &[[f32; 4]] {
+    match mesh.attribute(Mesh::ATTRIBUTE_COLOR) {
+        Some(VertexAttributeValues::Float32x4(colors)) => colors,
+        _ => panic!("Expected colors."),
+    }
+}
+
+#[test]
+pub fn evaluates_expressions() {
+    let road = properties([("kind", "road".into()), ("lanes", 3.into())]);
+    let path = properties([("kind", "path".into())]);
+
+    let kind = Expression::categorical("kind", [("road", 2.), ("rail", 3.)]);
+    assert_eq!(Some(2.), kind.evaluate(&road));
+    assert_eq!(None, kind.evaluate(&path));
+
+    let lanes = Expression::stepped("lanes", 1., [(2., 2.), (4., 4.)]);
+    assert_eq!(Some(2.), lanes.evaluate(&road));
+    assert_eq!(None, lanes.evaluate(&path));
+    assert_eq!(
+        Some(1.),
+        lanes.evaluate(&properties([("lanes", 1.5.into())]))
+    );
+    assert_eq!(Some(4.), lanes.evaluate(&properties([("lanes", 4.into())])));
+
+    let width = Expression::interpolated("lanes", [(1., 1.), (5., 3.)]);
+    assert_eq!(Some(2.), width.evaluate(&road));
+    assert_eq!(Some(3.), width.evaluate(&properties([("lanes", 9.into())])));
+    assert_eq!(Some(1.), width.evaluate(&properties([("lanes", 0.into())])));
+
+    let closure = Expression::function(|properties| {
+        Some(properties.property("kind")? == Value::from("path")).map(|is_path| {
+            if is_path {
+                0.5
+            } else {
+                1.
+            }
+        })
+    });
+    assert_eq!(Some(1.), closure.evaluate(&road));
+    assert_eq!(Some(0.5), closure.evaluate(&path));
+
+    // Missing properties fall back to the style's defaults.
+    let style = Style::new().with_stroke_width(kind).with_z_order(2.);
+    assert_eq!(
+        FeatureStyle {
+            stroke_width: 1.,
+            z_order: 2.,
+            ..Default::default()
+        },
+        style.resolve(&path)
+    );
+}
+
+#[test]
+pub fn colors_choropleth_fills_and_outlines() {
+    let style = Style::new()
+        .with_fill_color(Expression::interpolated(
+            "population",
+            [(0., LinearRgba::BLACK), (100., LinearRgba::WHITE)],
+        ))
+        .with_stroke_color(Expression::categorical(
+            "capital",
+            [(true, LinearRgba::RED)],
+        ))
+        .with_z_order(Expression::stepped("population", 0., [(50., 1.)]));
+    let features = [
+        (
+            Rect::new([0., 0.], [1., 1.]),
+            properties([("population", 25.into()), ("capital", true.into())]),
+        ),
+        (
+            Rect::new([1., 0.], [2., 1.]),
+            properties([("population", 100.into())]),
+        ),
+    ];
+    let meshes = features_to_meshes(features, &style).expect("Meshes");
+    let [GeometryMesh::Polygon(mesh)] = &meshes[..] else {
+        panic!("Expected one polygon mesh.")
+    };
+
+    let fill = &mesh.mesh;
+    assert_eq!(
+        [[0.25, 0.25, 0.25, 1.]; 4]
+            .into_iter()
+            .chain([[1.; 4]; 4])
+            .collect::<Vec<_>>(),
+        colors(fill)
+    );
+    let positions = positions(fill);
+    assert_eq!(
+        vec![0., 0., 0., 0., 1., 1., 1., 1.],
+        positions.iter().map(|[_, _, z]| *z).collect::<Vec<_>>()
+    );
+
+    let outline = &mesh.exterior_mesh;
+    assert_eq!(
+        [[1., 0., 0., 1.]; 4]
+            .into_iter()
+            .chain([[0., 0., 0., 1.]; 4])
+            .collect::<Vec<_>>(),
+        colors(outline)
+    );
+}
+
+#[test]
+pub fn styles_points_and_lines_per_feature() {
+    let style = Style::new()
+        .with_point_size(Expression::categorical("kind", [("city", 4.)]))
+        .with_stroke_width(Expression::interpolated("lanes", [(1., 1.), (3., 5.)]));
+    let mut ctx = BuildBevyMeshesContext::<f64>::new();
+    // Unstyled geometries added first get the default style.
+    ctx.add_geometry(&Geometry::LineString(vec![[0., 0.], [1., 0.]].into()))
+        .unwrap();
+    ctx.add_feature(
+        &Geometry::LineString(vec![[0., 1.], [1., 1.]].into()),
+        &properties([("lanes", 2.into())]),
+        &style,
+    )
+    .unwrap();
+    ctx.add_feature(
+        &Geometry::Point(Point::new(0., 0.)),
+        &properties([("kind", "city".into())]),
+        &style,
+    )
+    .unwrap();
+
+    let meshes = ctx.build_meshes().expect("Meshes");
+    let [GeometryMesh::Point(points), GeometryMesh::LineString(lines)] = &meshes[..] else {
+        panic!("Expected points and lines.")
+    };
+    assert_eq!(
+        (Some(4.), Some(LinearRgba::WHITE), 2),
+        (points[0].size, points[0].color, points[0].feature)
+    );
+    let Some(VertexAttributeValues::Float32(widths)) = lines.attribute(ATTRIBUTE_LINE_WIDTH) else {
+        panic!("Expected widths.")
+    };
+    assert_eq!(&vec![1., 1., 3., 3.], widths);
+    assert_eq!(4, colors(lines).len());
+}
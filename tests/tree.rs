@@ -0,0 +1,109 @@
+use geo_bevy::*;
+use geo_types::geometry::*;
+
+fn route_with_stops_and_area() -> Geometry {
+    Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+        Geometry::LineString(vec![[0., 0.], [1., 0.], [2., 1.]].into()),
+        Geometry::GeometryCollection(GeometryCollection::new_from(vec![
+            Geometry::Point(Point::new(0., 0.)),
+            Geometry::Point(Point::new(2., 1.)),
+        ])),
+        Geometry::Polygon(Polygon::new(
+            vec![[-1., -1.], [3., -1.], [3., 2.], [-1., 2.]].into(),
+            vec![],
+        )),
+    ]))
+}
+
+#[test]
+pub fn mirrors_nested_collections() {
+    let tree = geometry_to_mesh_tree(route_with_stops_and_area()).expect("Meshes");
+    let paths = tree
+        .meshes()
+        .into_iter()
+        .map(|(path, _)| path.to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(vec![vec![0], vec![1, 0], vec![1, 1], vec![2]], paths);
+
+    assert!(matches!(
+        tree.get(&[0]),
+        Some(GeometryMeshTree::Geometry { meshes, .. })
+            if matches!(meshes[..], [GeometryMesh::LineString(_)])
+    ));
+    let Some(GeometryMeshTree::Collection { path, members }) = tree.get(&[1]) else {
+        panic!("Expected the stops collection.")
+    };
+    assert_eq!(&[1], path.as_slice());
+    assert_eq!(2, members.len());
+    assert!(matches!(
+        tree.get(&[2]),
+        Some(GeometryMeshTree::Geometry { meshes, .. })
+            if matches!(meshes[..], [GeometryMesh::Polygon(_)])
+    ));
+    assert!(tree.get(&[0, 0]).is_none());
+}
+
+#[test]
+pub fn builds_members_separately() {
+    let tree = geometry_to_mesh_tree(route_with_stops_and_area()).expect("Meshes");
+    let Some(GeometryMeshTree::Geometry { meshes, .. }) = tree.get(&[1, 1]) else {
+        panic!("Expected a stop.")
+    };
+    let [GeometryMesh::Point(stop)] = &meshes[..] else {
+        panic!("Expected points.")
+    };
+    assert_eq!(vec![SpritePosition::new(2., 1.)], *stop);
+
+    // Flattened, the route, stops and area share builders and only one kind is returned.
+    assert!(matches!(
+        geometry_to_mesh(route_with_stops_and_area()),
+        Ok(GeometryMesh::Point(points)) if points.len() == 2
+    ));
+}
+
+#[test]
+pub fn keeps_empty_members() {
+    let tree = geometry_to_mesh_tree(Geometry::GeometryCollection(GeometryCollection::new_from(
+        vec![
+            Geometry::LineString(LineString::new(vec![])),
+            Geometry::MultiPoint(MultiPoint::new(vec![])),
+            Geometry::GeometryCollection(GeometryCollection::new_from(vec![])),
+            route_with_stops_and_area(),
+        ],
+    )))
+    .expect("Meshes");
+    for path in [[0], [1]] {
+        assert!(matches!(
+            tree.get(&path),
+            Some(GeometryMeshTree::Geometry { meshes, .. }) if meshes.is_empty()
+        ));
+    }
+    assert!(matches!(
+        tree.get(&[2]),
+        Some(GeometryMeshTree::Collection { members, .. }) if members.is_empty()
+    ));
+    assert_eq!(4, tree.meshes().len());
+}
+
+#[test]
+pub fn builds_outline_only_members() {
+    let tree = geometry_to_mesh_tree_with_options(
+        route_with_stops_and_area(),
+        MeshOptions {
+            polygon_mode: PolygonMode::Outline,
+            ..Default::default()
+        },
+    )
+    .expect("Meshes");
+    assert!(matches!(
+        tree.get(&[2]),
+        Some(GeometryMeshTree::Geometry { meshes, .. }) if matches!(
+            meshes[..],
+            [GeometryMesh::PolygonParts(PolygonMeshParts {
+                mesh: None,
+                exterior_mesh: Some(_),
+                ..
+            })]
+        )
+    ));
+}
@@ -0,0 +1,229 @@
+mod common;
+
+use bevy::{mesh::Indices, prelude::Mesh};
+use common::{positions, square_with_hole};
+use geo_bevy::*;
+use geo_types::geometry::*;
+
+fn triangle_count(mesh: &Mesh) -> usize {
+    match mesh.indices().expect("Indices") {
+        Indices::U16(indices) => indices.len() / 3,
+        Indices::U32(indices) => indices.len() / 3,
+    }
+}
+
+#[test]
+pub fn triangulates_with_earcutr_by_default() {
+    let PolygonMesh { mesh, .. } = polygon_to_mesh(square_with_hole(4., 1., 3.)).expect("Vertices");
+    assert_eq!(8, triangle_count(&mesh));
+}
+
+#[test]
+pub fn accepts_custom_triangulator() {
+    struct Fan;
+
+    impl Triangulator<f64> for Fan {
+        fn triangulate(&self, input: TriangulationInput<f64>) -> Result<Triangulation<f64>, Error> {
+            let exterior_len = input.rings().next().map_or(0, |ring| ring.len() / 2);
+            Ok(Triangulation {
+                triangle_indices: (1..exterior_len - 1).flat_map(|i| [0, i, i + 1]).collect(),
+                vertices: input.vertices,
+            })
+        }
+    }
+
+    let PolygonMesh { mesh, .. } =
+        polygon_to_mesh_with_triangulator(square_with_hole(4., 1., 3.), Fan).expect("Vertices");
+    assert_eq!(2, triangle_count(&mesh));
+}
+
+#[cfg(feature = "spade")]
+#[test]
+pub fn triangulates_with_constrained_delaunay() {
+    let PolygonMesh { mesh, .. } = polygon_to_mesh_with_triangulator(
+        square_with_hole(4., 1., 3.),
+        ConstrainedDelaunayTriangulator::default(),
+    )
+    .expect("Vertices");
+    assert_eq!(8, triangle_count(&mesh));
+}
+
+#[cfg(feature = "lyon")]
+#[test]
+pub fn triangulates_with_lyon() {
+    let PolygonMesh { mesh, .. } =
+        polygon_to_mesh_with_triangulator(square_with_hole(4., 1., 3.), LyonTriangulator)
+            .expect("Vertices");
+    assert_eq!(8, triangle_count(&mesh));
+}
+
+fn triangles(mesh: &Mesh) -> Vec<[[f32; 3]; 3]> {
+    let positions = positions(mesh);
+    let Some(Indices::U32(indices)) = mesh.indices() else {
+        panic!("Expected u32 indices.")
+    };
+    indices
+        .chunks_exact(3)
+        .map(|triangle| triangle.iter().map(|&i| positions[i as usize]))
+        .map(|mut vertices| [(); 3].map(|_| vertices.next().unwrap()))
+        .collect()
+}
+
+fn longest_edge(triangle: &[[f32; 3]; 3]) -> f32 {
+    (0..3)
+        .map(|i| {
+            let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+            (a[0] - b[0]).hypot(a[1] - b[1])
+        })
+        .fold(0., f32::max)
+}
+
+fn area(triangle: &[[f32; 3]; 3]) -> f32 {
+    let [a, b, c] = triangle;
+    ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])).abs() / 2.
+}
+
+#[test]
+pub fn refines_to_max_edge_length() {
+    let refinement = Refinement {
+        max_edge_length: Some(1.),
+        ..Default::default()
+    };
+    let PolygonMesh { mesh, .. } =
+        polygon_to_mesh_with_refinement(square_with_hole(4., 1., 3.), refinement)
+            .expect("Vertices");
+
+    let triangles = triangles(&mesh);
+    assert!(triangles
+        .iter()
+        .all(|triangle| longest_edge(triangle) <= 1.));
+    // Refinement only splits triangles, so the covered area is unchanged.
+    assert_eq!(12., triangles.iter().map(area).sum::<f32>());
+
+    // Limits that can't be reached fail instead of stopping short.
+    for refinement in [
+        Refinement {
+            max_edge_length: Some(0.),
+            ..Default::default()
+        },
+        Refinement {
+            max_area: Some(0.01),
+            max_vertices: 100,
+            ..Default::default()
+        },
+    ] {
+        assert!(matches!(
+            polygon_to_mesh_with_refinement(square_with_hole(4., 1., 3.), refinement),
+            Err(Error::InvalidRefinement(_))
+        ));
+        #[cfg(feature = "spade")]
+        assert!(matches!(
+            polygon_to_mesh_with_triangulator(
+                square_with_hole(4., 1., 3.),
+                ConstrainedDelaunayTriangulator::refined(refinement),
+            ),
+            Err(Error::InvalidRefinement(_))
+        ));
+    }
+}
+
+#[cfg(feature = "spade")]
+#[test]
+pub fn refines_with_delaunay_refinement() {
+    let refinement = Refinement {
+        max_area: Some(0.25),
+        ..Default::default()
+    };
+    let PolygonMesh { mesh, .. } = polygon_to_mesh_with_triangulator(
+        square_with_hole(4., 1., 3.),
+        ConstrainedDelaunayTriangulator::refined(refinement),
+    )
+    .expect("Vertices");
+
+    let triangles = triangles(&mesh);
+    assert!(triangles.iter().all(|triangle| area(triangle) <= 0.25));
+    assert!((12. - triangles.iter().map(area).sum::<f32>()).abs() < 1e-4);
+}
+
+#[test]
+pub fn triangulates_rects_and_triangles_directly() {
+    let mut builder = PolygonMeshBuilder::<f64>::new().with_triangulator(Fail);
+    builder
+        .add_rect(&Rect::new([0., 0.], [2., 1.]))
+        .expect("Vertices");
+    // Clockwise.
+    builder
+        .add_triangle(&Triangle::new(
+            [3., 0.].into(),
+            [3., 1.].into(),
+            [4., 0.].into(),
+        ))
+        .expect("Vertices");
+    assert_eq!(4 + 3, builder.len());
+
+    let PolygonMesh {
+        mesh,
+        exterior_mesh,
+        ..
+    } = PolygonMesh::try_from(builder).expect("Vertices");
+    let triangles = triangles(&mesh);
+    assert_eq!(3, triangles.len());
+    // All facing up.
+    assert!(triangles
+        .iter()
+        .all(|[a, b, c]| { (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]) > 0. }));
+    // Outlines are closed.
+    assert_eq!(2 * (4 + 3), exterior_mesh.indices().expect("Indices").len());
+}
+
+#[test]
+pub fn refines_and_validates_rects_and_triangles() {
+    let refinement = Refinement {
+        max_edge_length: Some(1.),
+        ..Default::default()
+    };
+    let mut builder = PolygonMeshBuilder::<f64>::new()
+        .with_triangulator(Fail)
+        .with_refinement(refinement);
+    builder
+        .add_rect(&Rect::new([0., 0.], [2., 1.]))
+        .expect("Vertices");
+    let PolygonMesh { mesh, .. } = PolygonMesh::try_from(builder).expect("Vertices");
+    let triangles = triangles(&mesh);
+    assert!(triangles
+        .iter()
+        .all(|triangle| longest_edge(triangle) <= 1.));
+    assert_eq!(2., triangles.iter().map(area).sum::<f32>());
+
+    // With validation on, they go through the triangulator like polygons.
+    let mut builder = PolygonMeshBuilder::<f64>::new().with_validation(Validation::Reject);
+    assert!(matches!(
+        builder.add_rect(&Rect::new([0., 0.], [2., 0.])),
+        Err(Error::InvalidGeometry(_))
+    ));
+    assert!(matches!(
+        builder.add_triangle(&Triangle::new(
+            [0., 0.].into(),
+            [1., 1.].into(),
+            [1., 1.].into(),
+        )),
+        Err(Error::InvalidGeometry(_))
+    ));
+    builder
+        .add_rect(&Rect::new([0., 0.], [2., 1.]))
+        .expect("Vertices");
+    assert_eq!(4, builder.len());
+    let mut builder = builder.with_triangulator(Fail);
+    assert!(matches!(
+        builder.add_rect(&Rect::new([0., 0.], [2., 1.])),
+        Err(Error::CouldNotBuildMesh)
+    ));
+}
+
+struct Fail;
+
+impl Triangulator<f64> for Fail {
+    fn triangulate(&self, _: TriangulationInput<f64>) -> Result<Triangulation<f64>, Error> {
+        Err(Error::CouldNotBuildMesh)
+    }
+}
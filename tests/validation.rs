@@ -0,0 +1,103 @@
+use geo_bevy::*;
+use geo_types::geometry::*;
+
+#[test]
+pub fn accepts_valid_polygon() {
+    let polygon = Polygon::new(vec![[0., 0.], [1., 0.], [1., 1.], [0., 1.]].into(), vec![]);
+    let report = validate_polygon(&polygon);
+    assert!(report.is_valid());
+    assert!(report.issues.is_empty());
+}
+
+#[test]
+pub fn reports_self_intersection_and_hole_outside_shell() {
+    let bowtie = Polygon::new(
+        vec![[0., 0.], [2., 2.], [2., 0.], [0., 2.]].into(),
+        vec![vec![[5., 5.], [6., 5.], [6., 6.]].into()],
+    );
+    let report = validate_polygon(&bowtie);
+
+    assert!(!report.is_valid());
+    assert!(report.issues.contains(&ValidationIssue::SelfIntersection {
+        ring: Ring::Exterior,
+        at: [1., 1.],
+    }));
+    assert!(report
+        .issues
+        .contains(&ValidationIssue::HoleOutsideShell(0)));
+    assert!(matches!(
+        polygon_to_mesh_with_validation(bowtie, Validation::Reject),
+        Err(Error::InvalidGeometry(_))
+    ));
+}
+
+#[test]
+pub fn repairs_bowtie_into_two_triangles() {
+    let bowtie = Polygon::new(
+        vec![[0., 0.], [2., 2.], [2., 0.], [1.5, 0.5], [0., 2.]].into(),
+        vec![],
+    );
+    let (repaired, report) = repair_polygon(&bowtie);
+
+    assert!(report.issues.contains(&ValidationIssue::CollinearPoints {
+        ring: Ring::Exterior,
+        count: 1
+    }));
+    assert_eq!(2, repaired.0.len());
+    for polygon in &repaired {
+        // Three corners plus the closing coordinate.
+        assert_eq!(4, polygon.exterior().0.len());
+    }
+
+    let (
+        PolygonMesh {
+            interior_meshes, ..
+        },
+        _,
+    ) = polygon_to_mesh_with_validation(bowtie, Validation::Repair).expect("Vertices");
+    assert!(interior_meshes.is_empty());
+}
+
+#[test]
+pub fn reports_holes_crossing_rings() {
+    // A U-shaped shell, and a hole with every corner inside it that bridges the notch.
+    let shell = LineString::from(vec![
+        [0., 0.],
+        [6., 0.],
+        [6., 6.],
+        [4., 6.],
+        [4., 2.],
+        [2., 2.],
+        [2., 6.],
+        [0., 6.],
+    ]);
+    let bridge = LineString::from(vec![[1., 4.], [1., 5.], [5., 5.], [5., 4.]]);
+    let report = validate_polygon(&Polygon::new(shell.clone(), vec![bridge.clone()]));
+    assert_eq!(vec![ValidationIssue::HoleOutsideShell(0)], report.issues);
+    let (repaired, _) = repair_polygon(&Polygon::new(shell, vec![bridge]));
+    assert!(repaired.0[0].interiors().is_empty());
+
+    // Two holes crossing each other, and one inside another.
+    let square =
+        |min: f64, max: f64| LineString::from(vec![[min, min], [min, max], [max, max], [max, min]]);
+    let polygon = Polygon::new(
+        vec![[0., 0.], [10., 0.], [10., 10.], [0., 10.]].into(),
+        vec![
+            square(1., 4.),
+            square(3., 6.),
+            square(7., 9.5),
+            square(7.5, 8.),
+        ],
+    );
+    let report = validate_polygon(&polygon);
+    assert!(!report.is_valid());
+    assert_eq!(
+        vec![
+            ValidationIssue::OverlappingHoles(0, 1),
+            ValidationIssue::OverlappingHoles(2, 3),
+        ],
+        report.issues
+    );
+    let (repaired, _) = repair_polygon(&polygon);
+    assert_eq!(2, repaired.0[0].interiors().len());
+}